@@ -0,0 +1,384 @@
+use core::ops::{Range, RangeFull};
+use alloc::{vec::Vec, alloc::{Allocator, Global}};
+
+use num::Float;
+
+use crate::{Saturate, SaturateMut};
+
+/// A cell of the table while it is being refined, with the function sampled at its midpoint and its quarter points.
+struct Cell<F, const N: usize>
+where
+    F: Float
+{
+    x0: F,
+    y0: [F; N],
+    x_mid: F,
+    y_mid: [F; N],
+    y_quarter: [[F; N]; 2],
+    error: F
+}
+
+impl<F, const N: usize> Cell<F, N>
+where
+    F: Float
+{
+    /// The midpoint is sampled unless it is already known, as it is when a cell is split at a quarter point of its parent.
+    fn new<Y>(func: &mut Y, x0: F, y0: [F; N], x1: F, y1: [F; N], y_mid: Option<[F; N]>) -> Self
+    where
+        Y: FnMut(F) -> [F; N]
+    {
+        let two = F::one() + F::one();
+        let x_mid = (x0 + x1)/two;
+        let x_quarter = [(x0 + x_mid)/two, (x_mid + x1)/two];
+        let y_mid = y_mid.unwrap_or_else(|| func(x_mid));
+        let y_quarter = x_quarter.map(&mut *func);
+
+        // A cell too narrow to split any further is as good as it gets
+        let error = if x0 < x_quarter[0] && x_quarter[0] < x_mid && x_mid < x_quarter[1] && x_quarter[1] < x1
+        {
+            [(x_quarter[0], y_quarter[0]), (x_mid, y_mid), (x_quarter[1], y_quarter[1])].into_iter()
+                .flat_map(|(x, y)| {
+                    let p = (x - x0)/(x1 - x0);
+                    let q = F::one() - p;
+                    y.into_iter()
+                        .zip(y0)
+                        .zip(y1)
+                        .map(move |((y, y0), y1)| (y - (y0*q + y1*p)).abs())
+                })
+                .fold(F::zero(), F::max)
+        }
+        else
+        {
+            F::zero()
+        };
+
+        Self {
+            x0,
+            y0,
+            x_mid,
+            y_mid,
+            y_quarter,
+            error
+        }
+    }
+}
+
+/// A lookup table with breakpoints placed where the function needs them, interpolated linearly.
+///
+/// The table is built up front. Starting from a coarse uniform grid, the cells where the straight line deviates the most from the
+/// function are split in two, until every cell is within `dy_max` or the table holds `max_len` breakpoints. Flat parts of the curve are
+/// thus covered by few, wide cells, and the knees by many, narrow ones.
+///
+/// The deviation of a cell is only checked at its midpoint and quarter points, so a feature narrower than a quarter of a cell can
+/// exceed `dy_max` unnoticed. `worst_case_error` measures the actual error.
+///
+/// Lookup is a binary search over the breakpoints, and the table never grows after it is built, so it is suitable for real-time use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveTable<F, const N: usize, A = Global>
+where
+    F: Float,
+    A: Allocator
+{
+    infinity: [[F; N]; 2],
+    x: Vec<F, A>,
+    y: Vec<[F; N], A>
+}
+
+impl<F, const N: usize> AdaptiveTable<F, N>
+where
+    F: Float
+{
+    pub fn new<Y>(func: Y, range: Range<F>, dy_max: F, max_len: usize) -> Self
+    where
+        Y: FnMut(F) -> [F; N]
+    {
+        Self::new_in(func, range, dy_max, max_len, Global)
+    }
+}
+
+impl<F, const N: usize, A> AdaptiveTable<F, N, A>
+where
+    F: Float,
+    A: Allocator + Clone
+{
+    /// Number of cells of the uniform grid the refinement starts from, so that narrow features are not missed entirely.
+    pub const INITIAL_RESOLUTION: usize = 16;
+
+    /// Like [`new`](AdaptiveTable::new), but the table and the scratch space used while refining it are allocated in `alloc`.
+    pub fn new_in<Y>(mut func: Y, range: Range<F>, dy_max: F, max_len: usize, alloc: A) -> Self
+    where
+        Y: FnMut(F) -> [F; N]
+    {
+        let Range {start, end} = range;
+        let infinity = [func(start), func(end)];
+        let max_len = max_len.max(2);
+
+        let resolution = Self::INITIAL_RESOLUTION.min(max_len - 1);
+        let r = F::from(resolution).unwrap();
+        let mut grid = Vec::with_capacity_in(resolution + 1, alloc.clone());
+        grid.extend((0..=resolution)
+            .map(|i| {
+                let p = F::from(i).unwrap()/r;
+                start*(F::one() - p) + end*p
+            })
+        );
+
+        let mut cells = Vec::with_capacity_in(resolution, alloc.clone());
+        let mut y0 = infinity[0];
+        for x in grid.windows(2)
+        {
+            let y1 = if x[1] == end {infinity[1]} else {func(x[1])};
+            cells.push(Cell::new(&mut func, x[0], y0, x[1], y1, None));
+            y0 = y1;
+        }
+
+        // Splits the worst cells first, so that a limited budget is spent where it helps the most
+        loop
+        {
+            let budget = max_len - (cells.len() + 1);
+            let mut split = Vec::new_in(alloc.clone());
+            split.extend((0..cells.len())
+                .filter(|&i| cells[i].error > dy_max)
+            );
+            if split.is_empty() || budget == 0
+            {
+                break
+            }
+            split.sort_by(|&i, &j| cells[j].error.partial_cmp(&cells[i].error).unwrap_or(core::cmp::Ordering::Equal));
+            split.truncate(budget);
+            split.sort_unstable();
+
+            let mut refined = Vec::with_capacity_in(cells.len() + split.len(), alloc.clone());
+            let mut split = split.into_iter().peekable();
+            for (i, cell) in cells.iter().enumerate()
+            {
+                let (x1, y1) = cells.get(i + 1)
+                    .map(|next| (next.x0, next.y0))
+                    .unwrap_or((end, infinity[1]));
+                if split.next_if_eq(&i).is_some()
+                {
+                    let [y_left, y_right] = cell.y_quarter;
+                    refined.push(Cell::new(&mut func, cell.x0, cell.y0, cell.x_mid, cell.y_mid, Some(y_left)));
+                    refined.push(Cell::new(&mut func, cell.x_mid, cell.y_mid, x1, y1, Some(y_right)));
+                }
+                else
+                {
+                    refined.push(Cell {..*cell});
+                }
+            }
+            cells = refined;
+        }
+
+        let mut x = Vec::with_capacity_in(cells.len() + 1, alloc.clone());
+        let mut y = Vec::with_capacity_in(cells.len() + 1, alloc);
+        for cell in cells
+        {
+            x.push(cell.x0);
+            y.push(cell.y0);
+        }
+        x.push(end);
+        y.push(infinity[1]);
+
+        Self {
+            infinity,
+            x,
+            y
+        }
+    }
+
+    /// Number of breakpoints.
+    pub fn len(&self) -> usize
+    {
+        self.x.len()
+    }
+    pub fn is_empty(&self) -> bool
+    {
+        self.x.is_empty()
+    }
+    /// The breakpoints, in ascending order.
+    pub fn breakpoints(&self) -> &[F]
+    {
+        &self.x
+    }
+
+    pub fn saturate(&self, x: F) -> [F; N]
+    {
+        let last = self.x.len() - 1;
+        if !(self.x[0] <= x && x <= self.x[last])
+        {
+            return self.infinity[(x > self.x[0]) as usize]
+        }
+
+        // The end of the range belongs to the last cell
+        let i = self.x.partition_point(|&x_i| x_i <= x).clamp(1, last) - 1;
+        let (x0, x1) = (self.x[i], self.x[i + 1]);
+        let p = (x - x0)/(x1 - x0);
+        let q = F::one() - p;
+
+        let mut y = self.y[i];
+        for (y, y1) in y.iter_mut()
+            .zip(self.y[i + 1])
+        {
+            *y = *y*q + y1*p;
+        }
+        y
+    }
+
+    /// The largest deviation of each output from `func`, checked at `oversampling` evenly spaced points inside each cell.
+    pub fn worst_case_error<Y>(&self, mut func: Y, oversampling: usize) -> [F; N]
+    where
+        Y: FnMut(F) -> [F; N]
+    {
+        let mut error = [F::zero(); N];
+        let Some(s) = F::from(oversampling + 1)
+        else
+        {
+            return error
+        };
+
+        for x in self.x.windows(2)
+        {
+            for j in 1..=oversampling
+            {
+                let p = F::from(j).unwrap()/s;
+                let x = x[0] + (x[1] - x[0])*p;
+                for ((e, y), y_interp) in error.iter_mut()
+                    .zip(func(x))
+                    .zip(self.saturate(x))
+                {
+                    *e = e.max((y - y_interp).abs());
+                }
+            }
+        }
+
+        error
+    }
+}
+
+impl<F, A> SaturateMut<F, RangeFull> for AdaptiveTable<F, 1, A>
+where
+    F: Float,
+    A: Allocator + Clone
+{
+    fn saturate_mut(&mut self, x: F, RangeFull: RangeFull) -> F
+    {
+        Saturate::saturate(self, x, ..)
+    }
+}
+impl<F, A> Saturate<F, RangeFull> for AdaptiveTable<F, 1, A>
+where
+    F: Float,
+    A: Allocator + Clone
+{
+    fn saturate(&self, x: F, RangeFull: RangeFull) -> F
+    {
+        let [y] = AdaptiveTable::saturate(self, x);
+        y
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::CacheTable;
+
+    use super::*;
+
+    fn tanh(x: f64) -> [f64; 1]
+    {
+        [(4.0*x).tanh()]
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -4.0..4.0;
+
+        let t0 = AdaptiveTable::new(tanh, -3.0..3.0, 1e-2, 32);
+        let mut t1 = CacheTable::new(tanh, -3.0..3.0, 31);
+
+        // Breakpoints cluster around the knees
+        crate::tests::plot(
+            "AdaptiveTable",
+            RANGE,
+            |x| [t0.saturate(x as f64)[0] as f32, t1.saturate(x as f64)[0] as f32]
+        )
+    }
+
+    #[test]
+    fn adaptive()
+    {
+        const OVERSAMPLING: usize = 16;
+        const DY_MAX: f64 = 1e-3;
+
+        // Meets the tolerance with fewer points than a uniform table
+        let table = AdaptiveTable::new(tanh, -3.0..3.0, DY_MAX, 1024);
+        let [e] = table.worst_case_error(tanh, OVERSAMPLING);
+        assert!(e < DY_MAX);
+        let [e_uniform] = CacheTable::new(tanh, -3.0..3.0, table.len() - 1).worst_case_error(OVERSAMPLING);
+        assert!(e < e_uniform);
+        assert!(table.breakpoints().is_sorted());
+
+        // Denser where the curve bends
+        let density = |x: Range<f64>| table.breakpoints()
+            .iter()
+            .filter(|&&x_i| x.contains(&x_i))
+            .count();
+        assert!(density(0.1..0.6) > 2*density(2.0..2.5));
+
+        // Never exceeds its budget
+        let small = AdaptiveTable::new(tanh, -3.0..3.0, DY_MAX, 24);
+        assert_eq!(small.len(), 24);
+        assert!(small.worst_case_error(tanh, OVERSAMPLING)[0] > DY_MAX);
+
+        // Matches the function at the breakpoints and holds its ends outside the range
+        for &x in table.breakpoints()
+        {
+            assert_eq!(table.saturate(x), tanh(x));
+        }
+        assert_eq!(table.saturate(-10.0), tanh(-3.0));
+        assert_eq!(table.saturate(10.0), tanh(3.0));
+
+        // Holds the right end, whatever the sign of the input
+        let positive = AdaptiveTable::new(tanh, 1.0..2.0, DY_MAX, 64);
+        assert_eq!(positive.saturate(0.5), tanh(1.0));
+        assert_eq!(positive.saturate(-0.5), tanh(1.0));
+        assert_eq!(positive.saturate(2.5), tanh(2.0));
+    }
+
+    #[test]
+    fn ripple()
+    {
+        const OVERSAMPLING: usize = 16;
+        const DY_MAX: f64 = 1e-2;
+
+        // A ripple crossing zero at every breakpoint and midpoint of the initial grid, which a check at the midpoints alone would miss
+        let cells = AdaptiveTable::<f64, 1>::INITIAL_RESOLUTION as f64;
+        let ripple = |x: f64| [(core::f64::consts::TAU*cells*x).sin()];
+        let table = AdaptiveTable::new(ripple, 0.0..1.0, DY_MAX, 1024);
+        assert!(table.len() > AdaptiveTable::<f64, 1>::INITIAL_RESOLUTION + 1);
+        assert!(table.worst_case_error(ripple, OVERSAMPLING)[0] < DY_MAX);
+    }
+
+    #[cfg(feature = "tubes")]
+    #[test]
+    fn triode()
+    {
+        use crate::{tubes::Tube12AX7, TriodeClassA};
+
+        const OVERSAMPLING: usize = 16;
+
+        // The plate voltage is flat in cutoff and saturation, and bends sharply between
+        let param = TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 1.5
+        };
+        let v_p = |v_g: f64| [param.load_line(&Tube12AX7, v_g).v_p];
+        let table = AdaptiveTable::new(v_p, -20.0..20.0, 0.1, 256);
+        assert!(table.worst_case_error(v_p, OVERSAMPLING)[0] < 0.2);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use num::Float;
+
+/// The quiescent operating point of a self-biased stage, with its plate dissipation.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BiasPoint<F>
+where
+    F: Float
+{
+    /// Cathode voltage, which is the grid bias.
+    pub v_k: F,
+    /// Plate voltage relative to ground.
+    pub v_p: F,
+    /// Plate current.
+    pub i_p: F,
+    /// Cathode current, including the screen current of a pentode.
+    pub i_k: F,
+    /// Plate dissipation in watts.
+    pub p_p: F,
+    /// Maximum plate dissipation of the tube model in watts.
+    pub p_max: F
+}
+
+impl<F> BiasPoint<F>
+where
+    F: Float
+{
+    /// Plate dissipation as a fraction of the maximum rating.
+    pub fn dissipation_ratio(&self) -> F
+    {
+        self.p_p/self.p_max
+    }
+
+    /// Whether the plate dissipates more than the tube is rated for.
+    pub fn is_overdissipated(&self) -> bool
+    {
+        self.p_p > self.p_max
+    }
+}
\ No newline at end of file
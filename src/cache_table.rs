@@ -5,15 +5,55 @@ use num::Float;
 
 use crate::SaturateMut;
 
+/// How a `CacheTable` interpolates between its samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Interpolation
+{
+    /// The closest sample, which steps between samples.
+    Nearest,
+    /// A straight line between the two closest samples, which kinks at each sample.
+    #[default]
+    Linear,
+    /// A cubic Hermite spline through the four closest samples, with the slope at each sample estimated by its neighbours.
+    ///
+    /// Smooth, but may overshoot next to sharp bends.
+    CatmullRom,
+    /// Like `CatmullRom`, but with the slopes limited so that the spline never overshoots the samples, keeping monotone curves monotone.
+    MonotoneCubic
+}
+
+impl Interpolation
+{
+    /// Slope of a Hermite spline at a sample, given the differences to its neighbouring samples.
+    fn slope<F>(self, d0: F, d1: F) -> F
+    where
+        F: Float
+    {
+        match self
+        {
+            Self::MonotoneCubic => if d0*d1 > F::zero()
+            {
+                // Harmonic mean, which never exceeds three times the smaller difference (Fritsch–Butland)
+                (d0*d1 + d0*d1)/(d0 + d1)
+            }
+            else
+            {
+                F::zero()
+            },
+            _ => (d0 + d1)/(F::one() + F::one())
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct CacheTableCurve<F, const N: usize, A = Global>
+pub(crate) struct CacheTableCurve<F, const N: usize, A = Global>
 where
     F: Float,
     A: Allocator
 {
-    infinity: [[F; N]; 2],
-    curve: Vec<[F; N], A>,
-    range: Range<F>
+    pub(crate) infinity: [[F; N]; 2],
+    pub(crate) curve: Vec<[F; N], A>,
+    pub(crate) range: Range<F>
 }
 
 impl<F, const N: usize, A> CacheTableCurve<F, N, A>
@@ -21,9 +61,9 @@ where
     F: Float,
     A: Allocator
 {
-    const MAX: usize = u16::MAX as usize - 1;
+    pub(crate) const MAX: usize = u16::MAX as usize - 1;
 
-    fn new_in<Y>(mut func: Y, mut range: Range<F>, resolution: usize, alloc: A) -> Self
+    pub(crate) fn new_in<Y>(mut func: Y, mut range: Range<F>, resolution: usize, alloc: A) -> Self
     where
         Y: FnMut(F) -> [F; N]
     {
@@ -40,16 +80,34 @@ where
         }
     }
 
-    fn reset(&mut self)
+    pub(crate) fn reset(&mut self)
     {
         self.curve.clear();
     }
-    fn is_set(&self, resolution: usize) -> bool
+    pub(crate) fn is_set(&self, resolution: usize) -> bool
     {
         self.curve.len() == resolution + 1
     }
 
-    fn refresh<Y>(&mut self, mut func: Y, resolution: usize)
+    /// The cell containing `x`, and how far into the cell it is.
+    pub(crate) fn index(&self, x: F, resolution: usize) -> Option<(usize, F)>
+    {
+        if resolution > 0 && self.is_set(resolution) && self.range.start <= x && x <= self.range.end && let Some(r) = F::from(resolution)
+        {
+            let z = r*(x - self.range.start)/(self.range.end - self.range.start);
+            if let Some(i) = z.floor().to_usize()
+            {
+                // The end of the range belongs to the last cell
+                let i = i.min(resolution - 1);
+                let p = z - F::from(i)?;
+
+                return Some((i, p))
+            }
+        }
+        None
+    }
+
+    pub(crate) fn refresh<Y>(&mut self, mut func: Y, resolution: usize)
     where
         Y: FnMut(F) -> [F; N]
     {
@@ -75,6 +133,107 @@ where
             .collect_into(&mut self.curve);
     }
 
+    /// Interpolates within the cell starting at sample `i`, at the fraction `p` of the way to the next sample.
+    fn interpolate(&self, interpolation: Interpolation, i: usize, p: F) -> [F; N]
+    {
+        let one = F::one();
+        let two = one + one;
+        let three = two + one;
+
+        let last = self.curve.len() - 1;
+        let y = |k: usize| unsafe {
+            *self.curve.get_unchecked(k.min(last))
+        };
+
+        let mut y0 = y(i);
+        let y1 = y(i + 1);
+        match interpolation
+        {
+            Interpolation::Nearest => if p + p < one
+            {
+                y0
+            }
+            else
+            {
+                y1
+            },
+            Interpolation::Linear => {
+                let q = one - p;
+                for (y0, y1) in y0.iter_mut()
+                    .zip(y1)
+                {
+                    *y0 = *y0*q + y1*p;
+                }
+                y0
+            },
+            Interpolation::CatmullRom | Interpolation::MonotoneCubic => {
+                // At the ends of the table, the missing neighbour continues the slope of the cell
+                let y_prev = if i > 0 {y(i - 1)} else {y0};
+                let y_next = if i + 1 < last {y(i + 2)} else {y1};
+
+                let p2 = p*p;
+                let p3 = p2*p;
+                let h00 = two*p3 - three*p2 + one;
+                let h10 = p3 - two*p2 + p;
+                let h01 = three*p2 - two*p3;
+                let h11 = p3 - p2;
+
+                for (n, y0) in y0.iter_mut()
+                    .enumerate()
+                {
+                    let d = y1[n] - *y0;
+                    let d_prev = if i > 0 {*y0 - y_prev[n]} else {d};
+                    let d_next = if i + 1 < last {y_next[n] - y1[n]} else {d};
+
+                    let m0 = interpolation.slope(d_prev, d);
+                    let m1 = interpolation.slope(d, d_next);
+
+                    *y0 = h00**y0 + h10*m0 + h01*y1[n] + h11*m1;
+                }
+                y0
+            }
+        }
+    }
+
+    /// Measures the error of the tables, with `interpolate` standing in for their interpolation. The curve must be refreshed first.
+    pub(crate) fn worst_case_error<Y, I>(&self, mut func: Y, resolution: usize, oversampling: usize, mut interpolate: I) -> [F; N]
+    where
+        Y: FnMut(F) -> [F; N],
+        I: FnMut(&Self, usize, F) -> [F; N]
+    {
+        let mut error = [F::zero(); N];
+        let (Some(r), Some(s)) = (F::from(resolution), F::from(oversampling + 1))
+        else
+        {
+            return error
+        };
+        let Range {start, end} = self.range;
+        let dx = (end - start)/r;
+
+        for i in 0..resolution
+        {
+            for j in 1..=oversampling
+            {
+                let (Some(i_f), Some(j)) = (F::from(i), F::from(j))
+                else
+                {
+                    continue
+                };
+                let p = j/s;
+                let y = func(start + (i_f + p)*dx);
+                let y_interp = interpolate(self, i, p);
+                for ((e, y), y_interp) in error.iter_mut()
+                    .zip(y)
+                    .zip(y_interp)
+                {
+                    *e = e.max((y - y_interp).abs());
+                }
+            }
+        }
+
+        error
+    }
+
     fn max(dx: F) -> Option<F>
     {
         F::from(Self::MAX)
@@ -118,6 +277,7 @@ where
 {
     func: Y,
     resolution: usize,
+    interpolation: Interpolation,
     curve: CacheTableCurve<F, N, A>
 }
 
@@ -147,10 +307,27 @@ where
         Self {
             func,
             resolution,
+            interpolation: Interpolation::default(),
             curve
         }
     }
 
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self
+    {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn interpolation(&self) -> Interpolation
+    {
+        self.interpolation
+    }
+    /// Changes how the samples are interpolated. The samples themselves are kept.
+    pub fn set_interpolation(&mut self, interpolation: Interpolation)
+    {
+        self.interpolation = interpolation
+    }
+
     pub fn func(&self) -> &Y
     {
         &self.func
@@ -166,53 +343,28 @@ where
         self.curve.reset()
     }
 
-    fn is_bounded(&self, x: F) -> bool
+    fn refresh(&mut self)
     {
-        self.curve.is_set(self.resolution) && self.curve.range.start <= x && x <= self.curve.range.end
-    }
-
-    fn index(&self, x: F) -> Option<(usize, usize, F, F)>
-    {
-        if self.is_bounded(x) && let Some(r) = F::from(self.resolution)
+        if !self.curve.is_set(self.resolution)
         {
-            let z = r*(x - self.curve.range.start)/(self.curve.range.end - self.curve.range.start);
-            if let (Some(i0), Some(i1)) = (
-                z.floor().to_usize(),
-                z.ceil().to_usize()
-            )
-            {
-                let p = z.fract();
-                let q = F::one() - p;
-
-                return Some((i0, i1, p, q))
-            }
+            self.curve.refresh(&mut self.func, self.resolution);
         }
-        None
     }
 
     pub fn saturate(&mut self, x: F) -> [F; N]
     {
-        if !self.curve.is_set(self.resolution)
-        {
-            self.curve.refresh(&mut self.func, self.resolution);
-        }
-        self.index(x)
-            .map(|(i0, i1, q, p)| {
-                let mut y = unsafe {
-                    *self.curve.curve.get_unchecked(i0)
-                };
-                let y1 = unsafe {
-                    *self.curve.curve.get_unchecked(i1)
-                };
+        self.refresh();
+        self.curve.index(x, self.resolution)
+            .map(|(i, p)| self.curve.interpolate(self.interpolation, i, p))
+            .unwrap_or_else(|| self.curve.infinity[x.is_sign_positive() as usize])
+    }
 
-                for (y, y1) in y.iter_mut()
-                    .zip(y1)
-                {
-                    *y = *y*q + y1*p;
-                }
-                
-                y
-            }).unwrap_or_else(|| self.curve.infinity[x.is_sign_positive() as usize])
+    /// The largest deviation of each output from the exact function, checked at `oversampling` evenly spaced points inside each cell.
+    pub fn worst_case_error(&mut self, oversampling: usize) -> [F; N]
+    {
+        self.refresh();
+        let interpolation = self.interpolation;
+        self.curve.worst_case_error(&mut self.func, self.resolution, oversampling, |curve, i, p| curve.interpolate(interpolation, i, p))
     }
 }
 
@@ -227,4 +379,107 @@ where
         let [y] = self.saturate(x);
         y
     }
+}
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use super::*;
+
+    const INTERPOLATIONS: [Interpolation; 4] = [
+        Interpolation::Nearest,
+        Interpolation::Linear,
+        Interpolation::CatmullRom,
+        Interpolation::MonotoneCubic
+    ];
+
+    fn table(interpolation: Interpolation, resolution: usize) -> CacheTable<f64, 1, impl FnMut(f64) -> [f64; 1]>
+    {
+        CacheTable::new(|x: f64| [(2.0*x).tanh()], -3.0..3.0, resolution)
+            .with_interpolation(interpolation)
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -4.0..4.0;
+
+        let mut tables = INTERPOLATIONS.map(|interpolation| table(interpolation, 8));
+
+        crate::tests::plot(
+            "CacheTable",
+            RANGE,
+            |x| tables.each_mut().map(|table| table.saturate_mut(x as f64, ..) as f32)
+        )
+    }
+
+    #[test]
+    fn linear()
+    {
+        // A straight line is reproduced between the samples, not mirrored within each cell
+        let mut table = CacheTable::new(|x: f64| [3.0*x - 1.0], -1.0..1.0, 4);
+        for x in [-0.9, -0.6, 0.1, 0.35, 0.8]
+        {
+            let [y] = table.saturate(x);
+            assert!((y - (3.0*x - 1.0)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn error()
+    {
+        const OVERSAMPLING: usize = 16;
+
+        // A straight line is exact, except when snapping to the nearest sample
+        for interpolation in INTERPOLATIONS
+        {
+            let [e] = CacheTable::new(|x: f64| [3.0*x - 1.0], -1.0..1.0, 4)
+                .with_interpolation(interpolation)
+                .worst_case_error(OVERSAMPLING);
+            assert_eq!(e < 1e-12, interpolation != Interpolation::Nearest);
+        }
+
+        // Cubic tables beat linear tables twice their size
+        let [nearest, linear, catmull_rom, monotone] = INTERPOLATIONS.map(|interpolation| table(interpolation, 64).worst_case_error(OVERSAMPLING)[0]);
+        assert!(linear < nearest);
+        assert!(catmull_rom < linear && monotone < linear);
+        let [linear_2x] = table(Interpolation::Linear, 128).worst_case_error(OVERSAMPLING);
+        assert!(catmull_rom < linear_2x && monotone < linear_2x);
+
+        // Only the monotone spline avoids overshooting a sharp knee
+        let overshoot = |interpolation| {
+            let mut table = CacheTable::new(|x: f64| [x.max(0.0)], -1.0..1.0, 5)
+                .with_interpolation(interpolation);
+            (0..=1000).map(|i| table.saturate_mut(-1.0 + i as f64*2e-3, ..))
+                .fold(0.0, |y_min: f64, y| y_min.min(y))
+        };
+        assert!(overshoot(Interpolation::CatmullRom) < -1e-3);
+        assert_eq!(overshoot(Interpolation::MonotoneCubic), 0.0);
+    }
+
+    #[cfg(feature = "tubes")]
+    #[test]
+    fn triode()
+    {
+        use crate::{tubes::Tube12AX7, TriodeCache, TriodeClassA};
+
+        const OVERSAMPLING: usize = 16;
+
+        // Cubic tube caches are more accurate than linear ones of the same size
+        let param = TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 1.5
+        };
+        let [linear, cubic] = [Interpolation::Linear, Interpolation::CatmullRom].map(|interpolation| {
+            let [vp, _] = TriodeCache::<_, Tube12AX7>::new(param, -20.0..20.0, 64)
+                .with_interpolation(interpolation)
+                .worst_case_error(&Tube12AX7, OVERSAMPLING);
+            vp
+        });
+        assert!(cubic < linear);
+        assert!(cubic < 1e-3*param.v_pp);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use num::{Float, traits::FloatConst};
+
+use super::DiodeModelParam;
+
+use crate::{f, thermal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AsymmetricDiodeClipper<F, MPos, MNeg = MPos>
+where
+    F: Float,
+    MPos: DiodeModelParam,
+    MNeg: DiodeModelParam
+{
+    r_d: F,
+    /// Number of diodes in series conducting positive voltages
+    n_pos: usize,
+    /// Number of diodes in series conducting negative voltages
+    n_neg: usize,
+    /// Cutoff frequency of the DC-blocking filter on the output (Hz)
+    dc_block: Option<F>,
+    temperature: F,
+    x1: F,
+    y1: F,
+    model_pos: MPos,
+    model_neg: MNeg
+}
+
+impl<F, MPos, MNeg> AsymmetricDiodeClipper<F, MPos, MNeg>
+where
+    F: Float + FloatConst,
+    MPos: DiodeModelParam,
+    MNeg: DiodeModelParam
+{
+    pub fn new(r_d: F, n_pos: usize, n_neg: usize, dc_block: Option<F>) -> Self
+    where
+        MPos: Default,
+        MNeg: Default
+    {
+        Self::with_models(r_d, MPos::default(), n_pos, MNeg::default(), n_neg, dc_block)
+    }
+
+    pub fn with_models(r_d: F, model_pos: MPos, n_pos: usize, model_neg: MNeg, n_neg: usize, dc_block: Option<F>) -> Self
+    {
+        Self {
+            r_d,
+            n_pos,
+            n_neg,
+            dc_block,
+            temperature: f!(thermal::T_ROOM),
+            x1: F::zero(),
+            y1: F::zero(),
+            model_pos,
+            model_neg
+        }
+    }
+
+    /// Temperature (Kelvin)
+    pub fn temperature(&self) -> F
+    {
+        self.temperature
+    }
+    pub fn set_temperature(&mut self, temperature: F)
+    {
+        self.temperature = temperature
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.x1 = F::zero();
+        self.y1 = F::zero();
+    }
+
+    pub fn saturate(&mut self, rate: F, x: F) -> F
+    {
+        let y = if x.is_sign_negative()
+        {
+            if self.n_neg == 0
+            {
+                x
+            }
+            else
+            {
+                -self.model_neg.diode().v_d(self.r_d, f!(self.n_neg), self.temperature, -x)
+            }
+        }
+        else if self.n_pos == 0
+        {
+            x
+        }
+        else
+        {
+            self.model_pos.diode().v_d(self.r_d, f!(self.n_pos), self.temperature, x)
+        };
+
+        match self.dc_block
+        {
+            Some(f_c) => {
+                // Bilinear transform of a first order RC high-pass filter
+                let two_rate_rc = rate/(F::PI()*f_c);
+                let one = F::one();
+
+                let y_hp = (two_rate_rc*(y - self.x1) + (two_rate_rc - one)*self.y1)/(two_rate_rc + one);
+                self.x1 = y;
+                self.y1 = y_hp;
+                y_hp
+            },
+            None => y
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::diodes::{Diode1N34A, Diode1N4148, Diode1N914, DiodeLEDRed};
+
+    use super::*;
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -10.0..10.0;
+        const RATE: f32 = 8000.0;
+
+        let r_d = 1e3;
+
+        let mut t0 = AsymmetricDiodeClipper::<_, Diode1N4148, DiodeLEDRed>::new(r_d, 1, 2, None);
+        let mut t1 = AsymmetricDiodeClipper::<_, Diode1N34A, Diode1N914>::new(r_d, 1, 1, None);
+        let mut t2 = AsymmetricDiodeClipper::<_, Diode1N4148>::new(r_d, 1, 2, None);
+        let mut t3 = AsymmetricDiodeClipper::<_, Diode1N4148>::new(r_d, 0, 1, None);
+
+        crate::tests::plot(
+            "AsymmetricDiodeClipper",
+            RANGE,
+            |x| [
+                t0.saturate(RATE, x),
+                t1.saturate(RATE, x),
+                t2.saturate(RATE, x),
+                t3.saturate(RATE, x)
+            ]
+        )
+    }
+
+    #[test]
+    fn dc_block()
+    {
+        const RATE: f32 = 8000.0;
+
+        let mut t = AsymmetricDiodeClipper::<_, Diode1N4148, DiodeLEDRed>::new(1e3, 1, 2, Some(20.0));
+
+        let mut y_mean = 0.0;
+        let n = RATE as usize*2;
+        for i in 0..n
+        {
+            let x = 5.0*(core::f32::consts::TAU*220.0*i as f32/RATE).sin();
+            let y = t.saturate(RATE, x);
+            if i >= n/2
+            {
+                y_mean += y/(n/2) as f32;
+            }
+        }
+
+        assert!(y_mean.abs() < 1e-2, "DC offset remains: {y_mean}");
+    }
+}
\ No newline at end of file
@@ -1,49 +1,54 @@
-use core::marker::PhantomData;
-
 use num::Float;
 
-use super::DiodeModel;
+use super::DiodeModelParam;
 
-use crate::f;
+use crate::{f, thermal};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct DiodeClipper<F, M>
 where
     F: Float,
-    M: DiodeModel
+    M: DiodeModelParam
 {
     r_d: F,
-    marker: PhantomData<M>
+    temperature: F,
+    model: M
 }
 
 impl<F, M> DiodeClipper<F, M>
 where
     F: Float,
-    M: DiodeModel
+    M: DiodeModelParam
 {
-    /// Temperature (Kelvin)
-    const T: f64 = 20.0 + 273.15;
-    /// Electron charge
-    const Q_E: f64 = 1.602176634e-19;
-    /// Boltzmann constant
-    const K: f64 = 1.38e-23;
-
     pub fn new(r_d: F) -> Self
+    where
+        M: Default
+    {
+        Self::with_model(r_d, M::default())
+    }
+
+    pub fn with_model(r_d: F, model: M) -> Self
     {
         Self {
             r_d,
-            marker: PhantomData
+            temperature: f!(thermal::T_ROOM),
+            model
         }
     }
 
+    /// Temperature (Kelvin)
+    pub fn temperature(&self) -> F
+    {
+        self.temperature
+    }
+    pub fn set_temperature(&mut self, temperature: F)
+    {
+        self.temperature = temperature
+    }
+
     pub fn saturate(&self, x: F) -> F
     {
-        let vf = f!(M::I_0)*self.r_d;
-        let alpha = f!(Self::Q_E/M::ETA/Self::K/Self::T);
-        let x_abs = x.abs();
-        let e = (vf*alpha).ln() + (vf + x_abs)*alpha;
-        let l = f!(crate::lambertw(e));
-        x.signum()*(x_abs + vf - l/alpha)
+        x.signum()*self.model.diode().v_d(self.r_d, F::one(), self.temperature, x.abs())
     }
 }
 
@@ -52,7 +57,10 @@ mod test
 {
     use core::ops::Range;
 
-    use crate::diodes::{Diode1N34A, Diode1N4001, Diode1N4148, Diode1N914};
+    use crate::diodes::{
+        Diode1N34A, Diode1N4001, Diode1N4148, Diode1N4733, Diode1N5817, Diode1N60, Diode1N914, DiodeBAT41, DiodeBAV99, DiodeLEDBlue, DiodeLEDGreen,
+        DiodeLEDRed, DiodeOA90
+    };
 
     use super::*;
 
@@ -67,6 +75,15 @@ mod test
         let t1 = DiodeClipper::<_, Diode1N914>::new(r_d);
         let t2 = DiodeClipper::<_, Diode1N4001>::new(r_d);
         let t3 = DiodeClipper::<_, Diode1N34A>::new(r_d);
+        let t4 = DiodeClipper::<_, DiodeBAV99>::new(r_d);
+        let t5 = DiodeClipper::<_, Diode1N4733>::new(r_d);
+        let t6 = DiodeClipper::<_, Diode1N5817>::new(r_d);
+        let t7 = DiodeClipper::<_, DiodeBAT41>::new(r_d);
+        let t8 = DiodeClipper::<_, DiodeOA90>::new(r_d);
+        let t9 = DiodeClipper::<_, Diode1N60>::new(r_d);
+        let t10 = DiodeClipper::<_, DiodeLEDRed>::new(r_d);
+        let t11 = DiodeClipper::<_, DiodeLEDGreen>::new(r_d);
+        let t12 = DiodeClipper::<_, DiodeLEDBlue>::new(r_d);
 
         crate::tests::plot(
             "DiodeClipper",
@@ -75,8 +92,40 @@ mod test
                 t0.saturate(x),
                 t1.saturate(x),
                 t2.saturate(x),
-                t3.saturate(x)
+                t3.saturate(x),
+                t4.saturate(x),
+                t5.saturate(x),
+                t6.saturate(x),
+                t7.saturate(x),
+                t8.saturate(x),
+                t9.saturate(x),
+                t10.saturate(x),
+                t11.saturate(x),
+                t12.saturate(x)
             ]
         )
     }
+
+    #[test]
+    fn temperature()
+    {
+        let r_d = 1e3;
+
+        let mut t0 = DiodeClipper::<f32, Diode1N4148>::new(r_d);
+        let mut t1 = DiodeClipper::<f32, Diode1N34A>::new(r_d);
+
+        let mut y_prev = [f32::INFINITY; 2];
+        for celsius in [-10.0f32, 20.0, 50.0]
+        {
+            t0.set_temperature(celsius + crate::thermal::ZERO_CELSIUS as f32);
+            t1.set_temperature(celsius + crate::thermal::ZERO_CELSIUS as f32);
+
+            let y = [t0.saturate(5.0), t1.saturate(5.0)];
+            for (y, y_prev) in y.into_iter().zip(y_prev)
+            {
+                assert!(y < y_prev, "Forward voltage must drop with temperature");
+            }
+            y_prev = y;
+        }
+    }
 }
\ No newline at end of file
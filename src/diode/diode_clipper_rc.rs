@@ -0,0 +1,212 @@
+use num::Float;
+
+use super::DiodeModelParam;
+
+use crate::{f, thermal};
+
+/// Numerical integration method used by `DiodeClipperRC`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum DiodeClipperRCSolver
+{
+    /// First order accurate, strongly damped. Never rings, but rolls off the highs a bit early.
+    BackwardEuler,
+    /// Second order accurate. Matches the analog frequency response closely.
+    #[default]
+    Trapezoidal
+}
+
+/// A resistor feeding a capacitor in parallel with a pair of anti-parallel diodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DiodeClipperRC<F, M>
+where
+    F: Float,
+    M: DiodeModelParam
+{
+    r_d: F,
+    c: F,
+    solver: DiodeClipperRCSolver,
+    temperature: F,
+    /// Capacitor voltage
+    v: F,
+    /// Capacitor current
+    i: F,
+    model: M
+}
+
+impl<F, M> DiodeClipperRC<F, M>
+where
+    F: Float,
+    M: DiodeModelParam
+{
+    pub fn new(r_d: F, c: F, solver: DiodeClipperRCSolver) -> Self
+    where
+        M: Default
+    {
+        Self::with_model(r_d, c, solver, M::default())
+    }
+
+    pub fn with_model(r_d: F, c: F, solver: DiodeClipperRCSolver, model: M) -> Self
+    {
+        Self {
+            r_d,
+            c,
+            solver,
+            temperature: f!(thermal::T_ROOM),
+            v: F::zero(),
+            i: F::zero(),
+            model
+        }
+    }
+
+    /// Temperature (Kelvin)
+    pub fn temperature(&self) -> F
+    {
+        self.temperature
+    }
+    pub fn set_temperature(&mut self, temperature: F)
+    {
+        self.temperature = temperature
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.v = F::zero();
+        self.i = F::zero();
+    }
+
+    pub fn saturate(&mut self, rate: F, x: F) -> F
+    {
+        let one = F::one();
+        let two = one + one;
+
+        let diode = self.model.diode();
+        let i_0 = diode.i_0(self.temperature);
+        let alpha = (f!(diode.eta)*crate::thermal::v_t(self.temperature)).recip();
+        let g = self.r_d.recip();
+
+        // The discretized capacitor is a conductance in parallel with a current source
+        let (g_c, i_c) = match self.solver
+        {
+            DiodeClipperRCSolver::BackwardEuler => {
+                let g_c = self.c*rate;
+                (g_c, g_c*self.v)
+            },
+            DiodeClipperRCSolver::Trapezoidal => {
+                let g_c = two*self.c*rate;
+                (g_c, g_c*self.v + self.i)
+            }
+        };
+
+        // Thevenin equivalent seen by the diodes
+        let r_th = (g + g_c).recip();
+        let x_th = (x*g + i_c)*r_th;
+
+        let r_s = f!(diode.r_s);
+        let v = x_th.signum()*diode.v_d(r_th, one, self.temperature, x_th.abs());
+
+        // Solves for the junction voltage, the series resistance carrying the diode current on top of it
+        let mut v_j = v - (x_th - v)/r_th*r_s;
+        let mut i_d = F::zero();
+
+        const NEWTON: usize = 16;
+
+        for _ in 0..NEWTON
+        {
+            let e = (v_j*alpha).exp();
+            let e_inv = e.recip();
+            i_d = i_0*(e - e_inv);
+            let di_d_dv = i_0*alpha*(e + e_inv);
+
+            let f = (v_j + i_d*r_s - x_th)/r_th + i_d;
+            let df_dv = (one + di_d_dv*r_s)/r_th + di_d_dv;
+
+            let delta = f/df_dv;
+            v_j = v_j - delta;
+            if delta.abs() <= F::epsilon()*v_j.abs()
+            {
+                break
+            }
+        }
+        let v = v_j + i_d*r_s;
+
+        self.i = match self.solver
+        {
+            DiodeClipperRCSolver::BackwardEuler => F::zero(),
+            DiodeClipperRCSolver::Trapezoidal => g_c*(v - self.v) - self.i
+        };
+        self.v = v;
+
+        v
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::{diodes::{Diode1N34A, Diode1N4148, DiodeBAT41, DiodeLEDRed}, DiodeClipper, DiodeModel};
+
+    use super::*;
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -10.0..10.0;
+        const RATE: f32 = 8000.0;
+
+        let r_d = 1e3;
+        let c = 10e-9;
+
+        let mut t0 = DiodeClipperRC::<_, Diode1N4148>::new(r_d, c, DiodeClipperRCSolver::Trapezoidal);
+        let mut t1 = DiodeClipperRC::<_, Diode1N4148>::new(r_d, c, DiodeClipperRCSolver::BackwardEuler);
+        let mut t2 = DiodeClipperRC::<_, Diode1N34A>::new(r_d, c, DiodeClipperRCSolver::Trapezoidal);
+        let mut t3 = DiodeClipperRC::<_, DiodeLEDRed>::new(r_d, c, DiodeClipperRCSolver::Trapezoidal);
+
+        crate::tests::plot(
+            "DiodeClipperRC",
+            RANGE,
+            |x| [
+                t0.saturate(RATE, x),
+                t1.saturate(RATE, x),
+                t2.saturate(RATE, x),
+                t3.saturate(RATE, x)
+            ]
+        )
+    }
+
+    #[test]
+    fn converges_to_static()
+    {
+        fn converges<M>()
+        where
+            M: DiodeModel + Default
+        {
+            const RATE: f32 = 48000.0;
+
+            let r_d = 1e3;
+            let c = 10e-9;
+
+            let clipper = DiodeClipper::<_, M>::new(r_d);
+            for solver in [DiodeClipperRCSolver::BackwardEuler, DiodeClipperRCSolver::Trapezoidal]
+            {
+                let mut t = DiodeClipperRC::<_, M>::new(r_d, c, solver);
+                for x in [-5.0, -0.3, 0.0, 0.4, 2.0, 10.0]
+                {
+                    let mut y = 0.0;
+                    for _ in 0..256
+                    {
+                        y = t.saturate(RATE, x);
+                    }
+                    let y_static = clipper.saturate(x);
+                    assert!((y - y_static).abs() < 1e-3, "{solver:?}: {y} != {y_static} for x = {x}");
+                }
+            }
+        }
+
+        // With and without series resistance
+        converges::<Diode1N4148>();
+        converges::<DiodeBAT41>();
+        converges::<DiodeLEDRed>();
+    }
+}
\ No newline at end of file
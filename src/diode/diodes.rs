@@ -1,5 +1,7 @@
 use crate::DiodeModel;
 
+// Silicon:
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub struct Diode1N4148;
 impl DiodeModel for Diode1N4148
@@ -24,10 +26,108 @@ impl DiodeModel for Diode1N4001
     const ETA: f64 = 1.9;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeBAV99;
+impl DiodeModel for DiodeBAV99
+{
+    const I_0: f64 = 2.5e-9;
+    const ETA: f64 = 1.8;
+    const R_S: f64 = 0.9;
+}
+
+/// 5.1V zener. Only forward conduction is modelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Diode1N4733;
+impl DiodeModel for Diode1N4733
+{
+    const I_0: f64 = 1e-11;
+    const ETA: f64 = 1.5;
+    const R_S: f64 = 1.5;
+}
+
+// Schottky:
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Diode1N5817;
+impl DiodeModel for Diode1N5817
+{
+    const I_0: f64 = 31.7e-6;
+    const ETA: f64 = 1.373;
+    const R_S: f64 = 0.051;
+    const E_G: f64 = 0.69;
+    const XTI: f64 = 2.0;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeBAT41;
+impl DiodeModel for DiodeBAT41
+{
+    const I_0: f64 = 1e-10;
+    const ETA: f64 = 1.02;
+    const R_S: f64 = 7.0;
+    const E_G: f64 = 0.69;
+    const XTI: f64 = 2.0;
+}
+
+// Germanium:
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub struct Diode1N34A;
 impl DiodeModel for Diode1N34A
 {
     const I_0: f64 = 1e-3;
     const ETA: f64 = 1.3;
+    const E_G: f64 = 0.67;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeOA90;
+impl DiodeModel for DiodeOA90
+{
+    const I_0: f64 = 3e-7;
+    const ETA: f64 = 1.5;
+    const R_S: f64 = 20.0;
+    const E_G: f64 = 0.67;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Diode1N60;
+impl DiodeModel for Diode1N60
+{
+    const I_0: f64 = 2.5e-7;
+    const ETA: f64 = 1.4;
+    const R_S: f64 = 10.0;
+    const E_G: f64 = 0.67;
+}
+
+// LEDs:
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeLEDRed;
+impl DiodeModel for DiodeLEDRed
+{
+    const I_0: f64 = 1e-18;
+    const ETA: f64 = 1.9;
+    const R_S: f64 = 2.0;
+    const E_G: f64 = 1.9;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeLEDGreen;
+impl DiodeModel for DiodeLEDGreen
+{
+    const I_0: f64 = 2.5e-20;
+    const ETA: f64 = 2.0;
+    const R_S: f64 = 2.5;
+    const E_G: f64 = 2.2;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiodeLEDBlue;
+impl DiodeModel for DiodeLEDBlue
+{
+    const I_0: f64 = 2.3e-19;
+    const ETA: f64 = 3.0;
+    const R_S: f64 = 10.0;
+    const E_G: f64 = 2.7;
 }
\ No newline at end of file
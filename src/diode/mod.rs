@@ -1,6 +1,8 @@
 moddef::moddef!(
     flat(pub) mod {
+        asymmetric_diode_clipper,
         diode_clipper,
+        diode_clipper_rc,
         model
     },
     pub mod {
@@ -1,7 +1,101 @@
+use num::Float;
+
+use crate::{f, thermal};
+
 pub trait DiodeModel
 {
     // Peak reverse current
     const I_0: f64;
     /// Ideality factor
     const ETA: f64;
+    /// Series resistance (Ohm)
+    const R_S: f64 = 0.0;
+    /// Bandgap energy (eV)
+    const E_G: f64 = 1.11;
+    /// Saturation current temperature exponent
+    const XTI: f64 = 3.0;
+    /// Temperature the parameters are specified at (Kelvin)
+    const T_NOM: f64 = thermal::T_ROOM;
+}
+
+/// Parameters of a diode model only known at runtime, e.g. imported from a SPICE model card.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DiodeParam
+{
+    /// Peak reverse current
+    pub i_0: f64,
+    /// Ideality factor
+    pub eta: f64,
+    /// Series resistance (Ohm)
+    pub r_s: f64,
+    /// Bandgap energy (eV)
+    pub e_g: f64,
+    /// Saturation current temperature exponent
+    pub xti: f64,
+    /// Temperature the parameters are specified at (Kelvin)
+    pub t_nom: f64
+}
+
+impl DiodeParam
+{
+    /// Saturation current at temperature `t` (Ampere)
+    pub(crate) fn i_0<F>(&self, t: F) -> F
+    where
+        F: Float
+    {
+        thermal::i_s(f!(self.i_0), f!(self.eta), f!(self.e_g), f!(self.xti), f!(self.t_nom), t)
+    }
+
+    /// Forward voltage of a single diode carrying the current `i` at temperature `t` (Volt)
+    pub fn v_f(&self, i: f64, t: f64) -> f64
+    {
+        self.eta*thermal::v_t(t)*(i/self.i_0(t)).ln_1p() + i*self.r_s
+    }
+
+    /// Voltage across `n` diodes in series at temperature `t`, driven by a non-negative voltage `x` through `r_d`.
+    pub(crate) fn v_d<F>(&self, r_d: F, n: F, t: F, x: F) -> F
+    where
+        F: Float
+    {
+        let r_s = f!(self.r_s)*n;
+        let r = r_d + r_s;
+        let vf = self.i_0(t)*r;
+        let alpha = (f!(self.eta)*thermal::v_t(t)*n).recip();
+        let e = (vf*alpha).ln() + (vf + x)*alpha;
+        let l = f!(crate::lambertw(e));
+        let vd = x + vf - l/alpha;
+
+        // The series resistance carries the same current as the junction
+        let i = (x - vd)/r;
+        vd + i*r_s
+    }
+}
+
+/// A diode model, either a preset implementing `DiodeModel` or a `DiodeParam`.
+pub trait DiodeModelParam
+{
+    fn diode(&self) -> DiodeParam;
+}
+impl<M> DiodeModelParam for M
+where
+    M: DiodeModel
+{
+    fn diode(&self) -> DiodeParam
+    {
+        DiodeParam {
+            i_0: M::I_0,
+            eta: M::ETA,
+            r_s: M::R_S,
+            e_g: M::E_G,
+            xti: M::XTI,
+            t_nom: M::T_NOM
+        }
+    }
+}
+impl DiodeModelParam for DiodeParam
+{
+    fn diode(&self) -> DiodeParam
+    {
+        *self
+    }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+use num::Float;
+
+use crate::{DiodeModelParam, DiodeParam};
+
+use super::{levenberg_marquardt, Fit, FitError};
+
+/// A measured point on a diode's forward I–V curve.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DiodePoint
+{
+    /// Forward voltage.
+    pub v: f64,
+    /// Forward current in amperes. Must be positive.
+    pub i: f64
+}
+
+/// Fits `I_0`, `ETA` and `R_S` of a diode to measured forward I–V points, taken at temperature `t` (Kelvin).
+///
+/// The fit starts at `guess`, which also supplies the bandgap and temperature exponent. The fitted parameters are nominal
+/// at `t`. Residuals are in volts, which weighs all decades of current equally.
+pub fn fit_diode<M>(points: &[DiodePoint], t: f64, guess: &M) -> Result<Fit<DiodeParam>, FitError>
+where
+    M: DiodeModelParam
+{
+    let guess = DiodeParam {
+        t_nom: t,
+        ..guess.diode()
+    };
+    let with = |&[i_0, eta, r_s]: &[f64; 3]| DiodeParam {
+        i_0: Float::exp(i_0),
+        eta: Float::exp(eta),
+        r_s,
+        ..guess
+    };
+    let (x, iterations) = levenberg_marquardt([Float::ln(guess.i_0), Float::ln(guess.eta), guess.r_s], points.len(), |x, k| {
+        let DiodePoint { v, i } = points[k];
+        with(x).v_f(i, t) - v
+    })?;
+
+    let param = with(&x);
+    let residuals: Vec<f64> = points.iter().map(|p| param.v_f(p.i, t) - p.v).collect();
+    Ok(Fit::new(param, residuals, iterations))
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{diodes::{Diode1N4148, Diode1N914}, thermal};
+
+    use super::*;
+
+    #[test]
+    fn diode()
+    {
+        let t = thermal::ZERO_CELSIUS + 25.0;
+        let truth = DiodeParam {
+            r_s: 0.6,
+            t_nom: t,
+            ..Diode1N914.diode()
+        };
+        let points: Vec<_> = (0..=50)
+            .map(|k| 1e-7*10f64.powf(k as f64/10.0))
+            .map(|i| DiodePoint { v: truth.v_f(i, t), i })
+            .collect();
+
+        let fit = fit_diode(&points, t, &Diode1N4148).unwrap();
+        assert!((fit.param.i_0/truth.i_0 - 1.0).abs() < 1e-3);
+        assert!((fit.param.eta/truth.eta - 1.0).abs() < 1e-3);
+        assert!((fit.param.r_s - truth.r_s).abs() < 1e-3);
+        assert!(fit.rms < 1e-6);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+use alloc::vec::Vec;
+use num::Float;
+
+use crate::{JFETModelParam, JFETParam};
+
+use super::{levenberg_marquardt, Fit, FitError};
+
+/// A measured point on an n-channel JFET's drain characteristics.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JFETPoint
+{
+    /// Gate-source voltage.
+    pub v_gs: f64,
+    /// Drain-source voltage.
+    pub v_ds: f64,
+    /// Drain current in amperes.
+    pub i_d: f64
+}
+
+/// Fits `BETA`, `VTO` and `LAMBDA` of a JFET to measured drain currents.
+///
+/// The fit starts at `guess`. Points in both the linear and saturated regions may be given, though `LAMBDA` is only determined by points at
+/// several drain-source voltages. Residuals are in amperes of drain current.
+pub fn fit_jfet<M>(points: &[JFETPoint], guess: &M) -> Result<Fit<JFETParam>, FitError>
+where
+    M: JFETModelParam
+{
+    let guess = guess.jfet();
+    let with = |&[beta, vto, lambda]: &[f64; 3]| JFETParam {
+        beta: Float::exp(beta),
+        vto,
+        lambda
+    };
+    let (x, iterations) = levenberg_marquardt([Float::ln(guess.beta), guess.vto, guess.lambda], points.len(), |x, k| {
+        let JFETPoint { v_gs, v_ds, i_d } = points[k];
+        with(x).i_d(v_gs, v_ds) - i_d
+    })?;
+
+    let param = with(&x);
+    let residuals: Vec<f64> = points.iter().map(|p| param.i_d(p.v_gs, p.v_ds) - p.i_d).collect();
+    Ok(Fit::new(param, residuals, iterations))
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::jfets::{JFET2N5457, JFETJ201};
+
+    use super::*;
+
+    #[test]
+    fn jfet()
+    {
+        let truth = JFETParam {
+            lambda: 2.3e-3,
+            ..JFET2N5457.jfet()
+        };
+        let points: Vec<_> = (0..=6)
+            .flat_map(|g| (1..=20).map(move |d| (-0.2*g as f64, 0.5*d as f64)))
+            .map(|(v_gs, v_ds)| JFETPoint {
+                v_gs,
+                v_ds,
+                i_d: truth.i_d(v_gs, v_ds)
+            })
+            .collect();
+
+        let fit = fit_jfet(&points, &JFETJ201).unwrap();
+        assert!((fit.param.beta/truth.beta - 1.0).abs() < 1e-3);
+        assert!((fit.param.vto - truth.vto).abs() < 1e-3);
+        assert!((fit.param.lambda - truth.lambda).abs() < 1e-5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use super::FitError;
+
+const MAX_ITERATIONS: usize = 500;
+const LAMBDA_INIT: f64 = 1e-3;
+const LAMBDA_MAX: f64 = 1e12;
+const TOLERANCE: f64 = 1e-12;
+const STEP: f64 = 1e-7;
+const MAX_STEP: f64 = 1.0;
+
+/// Minimizes the sum of squared residuals `residual(x, i)` for `i` in `0..m`, starting at `x`.
+///
+/// The Jacobian is approximated by forward differences, so the residual should be smooth in `x`, and each step is limited to a change of one in any
+/// parameter, so parameters should be scaled, e.g. logarithmically, accordingly. Returns the parameters and the number of
+/// iterations used.
+#[cfg_attr(not(any(feature = "diodes", feature = "jfets", feature = "tubes")), allow(unused))]
+pub(crate) fn levenberg_marquardt<const N: usize>(
+    mut x: [f64; N],
+    m: usize,
+    residual: impl Fn(&[f64; N], usize) -> f64
+) -> Result<([f64; N], usize), FitError>
+{
+    if m < N
+    {
+        return Err(FitError::TooFewPoints { points: m, parameters: N })
+    }
+
+    let cost = |x: &[f64; N]| (0..m).map(|i| residual(x, i)).map(|r| r*r).sum::<f64>();
+
+    let mut c = cost(&x);
+    if !c.is_finite()
+    {
+        return Err(FitError::NonFinite)
+    }
+
+    let mut lambda = LAMBDA_INIT;
+    for iteration in 0..MAX_ITERATIONS
+    {
+        // Normal equations J^T J and J^T r
+        let mut jtj = [[0.0; N]; N];
+        let mut jtr = [0.0; N];
+        for i in 0..m
+        {
+            let r = residual(&x, i);
+            let j: [f64; N] = core::array::from_fn(|k| {
+                let mut x_h = x;
+                let h = STEP*x[k].abs().max(1.0);
+                x_h[k] += h;
+                (residual(&x_h, i) - r)/h
+            });
+            for k in 0..N
+            {
+                jtr[k] += j[k]*r;
+                for l in 0..N
+                {
+                    jtj[k][l] += j[k]*j[l];
+                }
+            }
+        }
+
+        loop
+        {
+            let mut a = jtj;
+            for k in 0..N
+            {
+                a[k][k] += lambda*jtj[k][k].max(f64::MIN_POSITIVE);
+            }
+            let accepted = solve(a, jtr.map(|b| -b)).and_then(|mut delta| {
+                // Limit the step, so that a parameter with little influence can't run off before the others are fitted
+                let norm = delta.iter().fold(0.0, |norm: f64, d| norm.max(d.abs()));
+                if norm > MAX_STEP
+                {
+                    delta = delta.map(|d| d*MAX_STEP/norm)
+                }
+                let x_new: [f64; N] = core::array::from_fn(|k| x[k] + delta[k]);
+                let c_new = cost(&x_new);
+                (c_new.is_finite() && c_new <= c).then_some((x_new, c_new))
+            });
+            match accepted
+            {
+                Some((x_new, c_new)) => {
+                    let converged = c - c_new <= TOLERANCE*c;
+                    x = x_new;
+                    c = c_new;
+                    lambda = (lambda/10.0).max(f64::EPSILON);
+                    if converged
+                    {
+                        return Ok((x, iteration + 1))
+                    }
+                    break
+                },
+                None if lambda < LAMBDA_MAX => lambda *= 10.0,
+                None => return Ok((x, iteration + 1))
+            }
+        }
+    }
+
+    Ok((x, MAX_ITERATIONS))
+}
+
+/// Solves `a x = b` by Gaussian elimination with partial pivoting.
+#[cfg_attr(not(any(feature = "diodes", feature = "jfets", feature = "tubes")), allow(unused))]
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]>
+{
+    for k in 0..N
+    {
+        let p = (k..N).max_by(|&i, &j| a[i][k].abs().total_cmp(&a[j][k].abs()))?;
+        if a[p][k] == 0.0
+        {
+            return None
+        }
+        a.swap(k, p);
+        b.swap(k, p);
+        for i in k + 1..N
+        {
+            let (a_k, a_i) = a.split_at_mut(i);
+            let (a_k, a_i) = (&a_k[k], &mut a_i[0]);
+            let f = a_i[k]/a_k[k];
+            for (a_ij, a_kj) in a_i[k..].iter_mut().zip(&a_k[k..])
+            {
+                *a_ij -= f*a_kj;
+            }
+            b[i] -= f*b[k];
+        }
+    }
+    let mut x = [0.0; N];
+    for k in (0..N).rev()
+    {
+        x[k] = (b[k] - (k + 1..N).map(|j| a[k][j]*x[j]).sum::<f64>())/a[k][k];
+    }
+    x.iter().all(|x| x.is_finite()).then_some(x)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn fits_exponential()
+    {
+        let t: [f64; 7] = [0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0];
+        let y = t.map(|t| 2.5*(-1.3*t).exp());
+
+        let ([a, b], _) = levenberg_marquardt([1.0, 0.0], t.len(), |&[a, b], i| a*(b*t[i]).exp() - y[i]).unwrap();
+        assert!((a - 2.5).abs() < 1e-6);
+        assert!((b + 1.3).abs() < 1e-6);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,68 @@
+use core::fmt::Display;
+
+use alloc::vec::Vec;
+
+moddef::moddef!(
+    flat(pub) mod {
+        diode_fit for cfg(feature = "diodes"),
+        jfet_fit for cfg(feature = "jfets"),
+        tube_fit for cfg(feature = "tubes")
+    },
+    flat mod {
+        levenberg_marquardt
+    }
+);
+
+/// Result of fitting a model to measurements.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Fit<P>
+{
+    /// The fitted parameters.
+    pub param: P,
+    /// Model minus measurement for each point, in the unit of the measured quantity.
+    pub residuals: Vec<f64>,
+    /// Root-mean-square of the residuals.
+    pub rms: f64,
+    /// Number of Levenberg–Marquardt iterations used.
+    pub iterations: usize
+}
+
+#[cfg_attr(not(any(feature = "diodes", feature = "jfets", feature = "tubes")), allow(unused))]
+impl<P> Fit<P>
+{
+    fn new(param: P, residuals: Vec<f64>, iterations: usize) -> Self
+    {
+        let rms = (residuals.iter().map(|r| r*r).sum::<f64>()/residuals.len() as f64).sqrt();
+        Self {
+            param,
+            residuals,
+            rms,
+            iterations
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitError
+{
+    /// There are fewer measurements than free parameters.
+    TooFewPoints
+    {
+        points: usize, parameters: usize
+    },
+    /// The model could not be evaluated at the initial guess, or the measurements contain non-finite values.
+    NonFinite
+}
+
+impl Display for FitError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self
+        {
+            Self::TooFewPoints { points, parameters } => write!(f, "{points} points are too few to fit {parameters} parameters"),
+            Self::NonFinite => write!(f, "model residuals are not finite")
+        }
+    }
+}
+impl core::error::Error for FitError {}
\ No newline at end of file
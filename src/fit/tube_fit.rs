@@ -0,0 +1,190 @@
+use alloc::vec::Vec;
+use num::Float;
+
+use crate::{PentodeModelParam, PentodeParam, TriodeModelParam, TriodeParam};
+
+use super::{levenberg_marquardt, Fit, FitError};
+
+/// A measured point on a triode's plate curves.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TriodePoint
+{
+    /// Grid-cathode voltage.
+    pub v_g: f64,
+    /// Plate-cathode voltage.
+    pub v_p: f64,
+    /// Plate current in amperes.
+    pub i_p: f64
+}
+
+/// A measured point on a pentode's plate curves.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PentodePoint
+{
+    /// Grid-cathode voltage.
+    pub v_g: f64,
+    /// Screen-cathode voltage.
+    pub v_g2: f64,
+    /// Plate-cathode voltage.
+    pub v_p: f64,
+    /// Plate current in amperes.
+    pub i_p: f64,
+    /// Screen current in amperes, if measured.
+    pub i_g2: Option<f64>
+}
+
+fn koren(param: &TriodeParam) -> [f64; 5]
+{
+    [param.mu, param.ex, param.k_g1, param.k_p, param.k_vb].map(Float::ln)
+}
+fn with_koren(param: TriodeParam, x: &[f64; 5]) -> TriodeParam
+{
+    let [mu, ex, k_g1, k_p, k_vb] = x.map(Float::exp);
+    TriodeParam {
+        mu,
+        ex,
+        k_g1,
+        k_p,
+        k_vb,
+        ..param
+    }
+}
+
+/// Fits `MU`, `EX`, `K_G1`, `K_P` and `K_VB` of Koren's triode equation to measured plate curves.
+///
+/// The fit starts at `guess`, which also supplies the interelectrode capacitances and grid resistance. A preset of a similar tube is usually a good
+/// guess. Residuals are in amperes of plate current.
+pub fn fit_triode<M>(points: &[TriodePoint], guess: &M) -> Result<Fit<TriodeParam>, FitError>
+where
+    M: TriodeModelParam
+{
+    let guess = guess.triode();
+    let (x, iterations) = levenberg_marquardt(koren(&guess), points.len(), |x, i| {
+        let TriodePoint { v_g, v_p, i_p } = points[i];
+        with_koren(guess, x).i_p(v_g, v_p) - i_p
+    })?;
+
+    let param = with_koren(guess, &x);
+    let residuals = points.iter().map(|p| param.i_p(p.v_g, p.v_p) - p.i_p).collect();
+    Ok(Fit::new(param, residuals, iterations))
+}
+
+/// Fits `MU`, `EX`, `K_G1`, `K_P`, `K_VB` and `K_G2` of Koren's pentode equations to measured plate curves.
+///
+/// The triode parameters are fitted to the plate current. `K_G2` is then the least-squares fit to the points where the screen current is measured,
+/// or taken from `guess` if there are none. Residuals are in amperes of plate current.
+pub fn fit_pentode<M>(points: &[PentodePoint], guess: &M) -> Result<Fit<PentodeParam>, FitError>
+where
+    M: PentodeModelParam
+{
+    let guess = guess.pentode();
+    let (x, iterations) = levenberg_marquardt(koren(&guess.triode), points.len(), |x, i| {
+        let PentodePoint { v_g, v_g2, v_p, i_p, .. } = points[i];
+        PentodeParam {
+            triode: with_koren(guess.triode, x),
+            ..guess
+        }
+        .i_p(v_g, v_g2, v_p)
+            - i_p
+    })?;
+
+    let mut param = PentodeParam {
+        triode: with_koren(guess.triode, &x),
+        k_g2: 1.0
+    };
+
+    // The screen current is linear in 1/K_G2
+    let (sum_xy, sum_xx) = points
+        .iter()
+        .filter_map(|p| Some((param.i_g2(p.v_g, p.v_g2), p.i_g2?)))
+        .fold((0.0, 0.0), |(sum_xy, sum_xx), (x, y)| (sum_xy + x*y, sum_xx + x*x));
+    param.k_g2 = if sum_xy > 0.0 { sum_xx/sum_xy } else { guess.k_g2 };
+
+    let residuals: Vec<f64> = points.iter().map(|p| param.i_p(p.v_g, p.v_g2, p.v_p) - p.i_p).collect();
+    Ok(Fit::new(param, residuals, iterations))
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::tubes::{Tube12AU7, Tube6L6CG};
+
+    use super::*;
+
+    fn perturbed(param: TriodeParam) -> TriodeParam
+    {
+        TriodeParam {
+            mu: param.mu*1.3,
+            ex: param.ex*0.9,
+            k_g1: param.k_g1*0.7,
+            k_p: param.k_p*1.4,
+            k_vb: param.k_vb*0.8,
+            ..param
+        }
+    }
+
+    fn assert_close(fit: &TriodeParam, truth: &TriodeParam)
+    {
+        for (x, y) in koren(fit).into_iter().zip(koren(truth))
+        {
+            assert!((x - y).abs() < 1e-3, "{fit:?} != {truth:?}")
+        }
+    }
+
+    #[test]
+    fn triode()
+    {
+        let truth = Tube12AU7.triode();
+        let points: Vec<_> = (0..=8)
+            .flat_map(|g| (1..=40).map(move |p| (-0.5*g as f64, 10.0*p as f64)))
+            .map(|(v_g, v_p)| TriodePoint {
+                v_g,
+                v_p,
+                i_p: truth.i_p(v_g, v_p)
+            })
+            .collect();
+
+        let fit = fit_triode(&points, &perturbed(truth)).unwrap();
+        assert_close(&fit.param, &truth);
+        assert!(fit.rms < 1e-7);
+        assert_eq!(fit.residuals.len(), points.len());
+    }
+
+    #[test]
+    fn pentode()
+    {
+        let truth = Tube6L6CG.pentode();
+        let points: Vec<_> = (0..=8)
+            .flat_map(|g| (1..=16).map(move |p| (-5.0*g as f64, 25.0*p as f64)))
+            .map(|(v_g, v_p)| PentodePoint {
+                v_g,
+                v_g2: 250.0,
+                v_p,
+                i_p: truth.i_p(v_g, 250.0, v_p),
+                i_g2: Some(truth.i_g2(v_g, 250.0))
+            })
+            .collect();
+
+        let guess = PentodeParam {
+            triode: perturbed(truth.triode),
+            k_g2: 1.0
+        };
+        let fit = fit_pentode(&points, &guess).unwrap();
+        assert_close(&fit.param.triode, &truth.triode);
+        assert!((fit.param.k_g2/truth.k_g2 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn too_few_points()
+    {
+        let points = [TriodePoint {
+            v_g: 0.0,
+            v_p: 100.0,
+            i_p: 1e-3
+        }];
+        assert_eq!(
+            fit_triode(&points, &Tube12AU7),
+            Err(FitError::TooFewPoints { points: 1, parameters: 5 })
+        );
+    }
+}
\ No newline at end of file
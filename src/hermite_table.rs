@@ -0,0 +1,242 @@
+use core::ops::{Range, RangeFull};
+use alloc::alloc::{Allocator, Global};
+
+use num::Float;
+
+use crate::{cache_table::CacheTableCurve, SaturateMut};
+
+/// A lookup table of a function and its derivative, interpolated by cubic Hermite splines.
+///
+/// `func` returns the value and the derivative at each sample. Using the true derivative instead of estimating it from the neighbouring
+/// samples, the interpolated curve and its slope are both continuous, without evaluating the function any more often than `CacheTable`
+/// does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HermiteTable<F, Y, A = Global>
+where
+    F: Float,
+    Y: FnMut(F) -> [F; 2],
+    A: Allocator
+{
+    func: Y,
+    resolution: usize,
+    curve: CacheTableCurve<F, 2, A>
+}
+
+impl<F, Y> HermiteTable<F, Y>
+where
+    F: Float,
+    Y: FnMut(F) -> [F; 2]
+{
+    pub fn new(func: Y, range: Range<F>, resolution: usize) -> Self
+    {
+        Self::new_in(func, range, resolution, Global)
+    }
+}
+
+impl<F, Y, A> HermiteTable<F, Y, A>
+where
+    F: Float,
+    Y: FnMut(F) -> [F; 2],
+    A: Allocator + Clone
+{
+    pub fn new_in(mut func: Y, range: Range<F>, mut resolution: usize, alloc: A) -> Self
+    {
+        resolution = resolution.min(CacheTableCurve::<F, 2, A>::MAX);
+        let curve = CacheTableCurve::new_in(&mut func, range, resolution, alloc);
+
+        Self {
+            func,
+            resolution,
+            curve
+        }
+    }
+
+    pub fn func(&self) -> &Y
+    {
+        &self.func
+    }
+    pub fn func_mut(&mut self) -> &mut Y
+    {
+        self.reset();
+        &mut self.func
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.curve.reset()
+    }
+
+    fn refresh(&mut self)
+    {
+        if !self.curve.is_set(self.resolution)
+        {
+            self.curve.refresh(&mut self.func, self.resolution);
+        }
+    }
+
+    /// Interpolates within the cell starting at sample `i`, at the fraction `p` of the way to the next sample.
+    fn interpolate(curve: &CacheTableCurve<F, 2, A>, resolution: usize, i: usize, p: F) -> [F; 2]
+    {
+        let one = F::one();
+        let two = one + one;
+        let three = two + one;
+        let six = three + three;
+
+        let [y0, dy0] = unsafe {
+            *curve.curve.get_unchecked(i)
+        };
+        let [y1, dy1] = unsafe {
+            *curve.curve.get_unchecked(i + 1)
+        };
+        let Range {start, end} = curve.range;
+        let dx = (end - start)/F::from(resolution).unwrap_or(one);
+
+        let p2 = p*p;
+        let p3 = p2*p;
+
+        let h00 = two*p3 - three*p2 + one;
+        let h10 = p3 - two*p2 + p;
+        let h01 = three*p2 - two*p3;
+        let h11 = p3 - p2;
+        let y = h00*y0 + h10*dx*dy0 + h01*y1 + h11*dx*dy1;
+
+        // Derivative of the spline, which passes exactly through the sampled derivatives
+        let dh00 = six*(p2 - p);
+        let dh10 = three*p2 - two*(p + p) + one;
+        let dh01 = -dh00;
+        let dh11 = three*p2 - two*p;
+        let dy = (dh00*y0 + dh01*y1)/dx + dh10*dy0 + dh11*dy1;
+
+        [y, dy]
+    }
+
+    /// The interpolated value and derivative at `x`.
+    pub fn saturate(&mut self, x: F) -> [F; 2]
+    {
+        self.refresh();
+        self.curve.index(x, self.resolution)
+            .map(|(i, p)| Self::interpolate(&self.curve, self.resolution, i, p))
+            .unwrap_or_else(|| self.curve.infinity[x.is_sign_positive() as usize])
+    }
+
+    /// The largest deviation of the value and derivative from the exact function, checked at `oversampling` evenly spaced points inside
+    /// each cell.
+    pub fn worst_case_error(&mut self, oversampling: usize) -> [F; 2]
+    {
+        self.refresh();
+        let resolution = self.resolution;
+        self.curve.worst_case_error(&mut self.func, resolution, oversampling, |curve, i, p| Self::interpolate(curve, resolution, i, p))
+    }
+}
+
+impl<F, Y, A> SaturateMut<F, RangeFull> for HermiteTable<F, Y, A>
+where
+    F: Float,
+    Y: FnMut(F) -> [F; 2],
+    A: Allocator + Clone
+{
+    fn saturate_mut(&mut self, x: F, RangeFull: RangeFull) -> F
+    {
+        let [y, _] = self.saturate(x);
+        y
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::{CacheTable, Interpolation};
+
+    use super::*;
+
+    fn tanh(x: f64) -> [f64; 2]
+    {
+        let y = (2.0*x).tanh();
+        [y, 2.0*(1.0 - y*y)]
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -4.0..4.0;
+
+        let mut t0 = CacheTable::new(tanh, -3.0..3.0, 8);
+        let mut t1 = HermiteTable::new(tanh, -3.0..3.0, 8);
+
+        // Value and slope, of the linear table and the Hermite table
+        crate::tests::plot(
+            "HermiteTable",
+            RANGE,
+            |x| {
+                let [y0, dy0] = t0.saturate(x as f64);
+                let [y1, dy1] = t1.saturate(x as f64);
+                [y0, dy0, y1, dy1].map(|y| y as f32)
+            }
+        )
+    }
+
+    #[test]
+    fn smooth()
+    {
+        const OVERSAMPLING: usize = 16;
+        const RESOLUTION: usize = 32;
+
+        // More accurate than a spline estimating the slopes from the same samples
+        let [y, dy] = HermiteTable::new(tanh, -3.0..3.0, RESOLUTION).worst_case_error(OVERSAMPLING);
+        let [y_catmull_rom, _] = CacheTable::new(tanh, -3.0..3.0, RESOLUTION)
+            .with_interpolation(Interpolation::CatmullRom)
+            .worst_case_error(OVERSAMPLING);
+        assert!(y < y_catmull_rom);
+        assert!(dy < 1e-2);
+
+        // The slope is continuous across samples
+        let mut table = HermiteTable::new(tanh, -3.0..3.0, RESOLUTION);
+        let dx = 6.0/RESOLUTION as f64;
+        for i in 1..RESOLUTION
+        {
+            let x = -3.0 + i as f64*dx;
+            let [_, dy_below] = table.saturate(x - 1e-9);
+            let [_, dy_above] = table.saturate(x + 1e-9);
+            assert!((dy_below - dy_above).abs() < 1e-6);
+        }
+    }
+
+    #[cfg(feature = "tubes")]
+    #[test]
+    fn tubes()
+    {
+        use crate::{tubes::{Tube12AX7, Tube6L6CG}, PentodeCache, PentodeClassA, PentodeHermiteCache, TriodeCache, TriodeClassA, TriodeHermiteCache};
+
+        const OVERSAMPLING: usize = 16;
+
+        // The gain of a cached tube drives the Miller effect, so it must beat a linear cache of the same size as well
+        let param = TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 1.5
+        };
+        let [vp, a] = TriodeHermiteCache::<_, Tube12AX7>::new(param, -20.0..20.0, 64)
+            .worst_case_error(&Tube12AX7, OVERSAMPLING);
+        let [vp_linear, a_linear] = TriodeCache::<_, Tube12AX7>::new(param, -20.0..20.0, 64)
+            .worst_case_error(&Tube12AX7, OVERSAMPLING);
+        assert!(vp < vp_linear && vp < 1e-3*param.v_pp);
+        assert!(a < a_linear);
+
+        let param = PentodeClassA {
+            r_i: 1e3,
+            r_p: 3.4e3,
+            v_pp: 400.0,
+            v_g2: 400.0,
+            v_c: 10.0
+        };
+        let [vp, a] = PentodeHermiteCache::<_, Tube6L6CG>::new(param, -60.0..60.0, 128)
+            .worst_case_error(&Tube6L6CG, OVERSAMPLING);
+        let [vp_linear, a_linear] = PentodeCache::<_, Tube6L6CG>::new(param, -60.0..60.0, 128)
+            .worst_case_error(&Tube6L6CG, OVERSAMPLING);
+        assert!(vp < vp_linear && vp < 1e-3*param.v_pp);
+        assert!(a < a_linear);
+    }
+}
\ No newline at end of file
@@ -1,53 +1,81 @@
-use core::{marker::PhantomData, ops::RangeTo};
+use core::ops::RangeTo;
 
 use num::Float;
 use crate::{f, Saturate, SoftExp};
 
-use super::JFETModel;
+use super::JFETModelParam;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct JFETBuffer<F, M>
 where
     F: Float,
-    M: JFETModel
+    M: JFETModelParam
 {
     r_s: F,
     v_dd: F,
-    marker: PhantomData<M>
+    model: M
 }
 
 impl<F, M> JFETBuffer<F, M>
 where
     F: Float,
     SoftExp: Saturate<F, RangeTo<F>>,
-    M: JFETModel
+    M: JFETModelParam
 {
     pub fn new(r_s: F, v_dd: F) -> Self
+    where
+        M: Default
+    {
+        Self::with_model(r_s, v_dd, M::default())
+    }
+
+    pub fn with_model(r_s: F, v_dd: F, model: M) -> Self
     {
         Self {
             r_s,
             v_dd,
-            marker: PhantomData
+            model
         }
     }
 
+    /// Source voltage for a gate `vgo` above pinch-off, in saturation.
+    ///
+    /// Channel-length modulation raises the current by `1 + λ·V_DS`. It is applied with the drain-source voltage found without it,
+    /// which is exact to first order in `λ`.
+    fn v_s(&self, vgo: F) -> F
+    {
+        let one = F::one();
+
+        let jfet = self.model.jfet();
+        let solve = |beta: F| {
+            let two_beta = beta + beta;
+            let four_beta = two_beta + two_beta;
+            vgo + (one - (four_beta*self.r_s*vgo + one).sqrt())/(two_beta*self.r_s)
+        };
+
+        let beta = f!(jfet.beta);
+        let vs = solve(beta);
+        if jfet.lambda == 0.0
+        {
+            return vs
+        }
+        solve(beta*(one + f!(jfet.lambda)*(self.v_dd - vs).max(F::zero())))
+    }
+
     pub fn saturate(&self, x: F) -> F
     {
         const R_DS: f64 = 2.0;
 
         let zero = F::zero();
-        let one = F::one();
 
-        let vgo = x - f!(M::VTO);
-        let two_beta = f!(M::BETA*2.0);
-        let four_beta = f!(M::BETA*4.0);
-        let mut vs = vgo + (one - (four_beta*self.r_s*vgo + one).sqrt())/(two_beta*self.r_s);
+        let vto = f!(self.model.jfet().vto);
+
+        let mut vs = self.v_s(x - vto);
 
         let id = vs/self.r_s;
         vs = SoftExp.saturate(vs, ..(self.v_dd - id*f!(R_DS)).max(zero));
 
-        vs = vs + f!(M::VTO) - (one - (-four_beta*self.r_s*f!(M::VTO) + one).sqrt())/(two_beta*self.r_s);
-        vs
+        vs - self.v_s(-vto)
     }
 }
 
@@ -56,7 +84,7 @@ mod test
 {
     use core::ops::Range;
 
-    use crate::jfets::JFET2N5458;
+    use crate::{jfets::{JFET2N3819, JFET2N5457, JFET2N5458, JFET2N5952, JFET2SK30A, JFETJ201, JFETMPF102}, JFETParam};
 
     use super::*;
 
@@ -68,13 +96,74 @@ mod test
         let r_s = 100e3;
         
         let t0 = JFETBuffer::<_, JFET2N5458>::new(r_s, 9.0);
+        let t1 = JFETBuffer::<_, JFET2N5457>::new(r_s, 9.0);
+        let t2 = JFETBuffer::<_, JFET2N5952>::new(r_s, 9.0);
+        let t3 = JFETBuffer::<_, JFET2N3819>::new(r_s, 9.0);
+        let t4 = JFETBuffer::<_, JFETJ201>::new(r_s, 9.0);
+        let t5 = JFETBuffer::<_, JFETMPF102>::new(r_s, 9.0);
+        let t6 = JFETBuffer::<_, JFET2SK30A>::new(r_s, 9.0);
 
         crate::tests::plot(
             "JFETBuffer",
             RANGE,
             |x| [
                 t0.saturate(x),
+                t1.saturate(x),
+                t2.saturate(x),
+                t3.saturate(x),
+                t4.saturate(x),
+                t5.saturate(x),
+                t6.saturate(x)
             ]
         )
     }
+
+    #[test]
+    fn presets()
+    {
+        const DX: f64 = 1e-2;
+
+        // Saturation current and pinch-off voltage within the datasheet limits
+        let presets = [
+            (JFET2N5458.jfet(), 2e-3..9e-3, -7.0..-1.0),
+            (JFET2N5457.jfet(), 1e-3..5e-3, -6.0..-0.5),
+            (JFET2N5952.jfet(), 4e-3..8e-3, -3.5..-1.3),
+            (JFET2N3819.jfet(), 2e-3..20e-3, -8.0..-0.5),
+            (JFETJ201.jfet(), 0.2e-3..1e-3, -1.5..-0.3),
+            (JFETMPF102.jfet(), 2e-3..20e-3, -8.0..-0.5),
+            (JFET2SK30A.jfet(), 2.6e-3..6.5e-3, -5.0..-0.4)
+        ];
+        for (jfet, i_dss, v_p) in presets
+        {
+            assert!(i_dss.contains(&jfet.i_d(0.0, 10.0)));
+            assert!(v_p.contains(&jfet.vto));
+
+            // A source follower with a large source resistor passes small signals almost unattenuated
+            let buffer = JFETBuffer::with_model(100e3, 9.0, jfet);
+            let gain = (buffer.saturate(DX) - buffer.saturate(-DX))/(DX + DX);
+            assert!(gain > 0.9 && gain < 1.0);
+        }
+    }
+
+    #[test]
+    fn lambda()
+    {
+        let r_s = 10e3;
+        let v_dd = 9.0;
+
+        // The drain current through the source resistor sets the source voltage, including channel-length modulation
+        let jfet = JFETParam {
+            lambda: 2.3e-3,
+            ..JFET2N5457.jfet()
+        };
+        let buffer = JFETBuffer::with_model(r_s, v_dd, jfet);
+        let buffer_ideal = JFETBuffer::with_model(r_s, v_dd, JFET2N5457);
+        for x in [-0.5, 0.0, 0.5, 1.0, 2.0]
+        {
+            let [v_s, v_s_ideal] = [buffer.v_s(x - jfet.vto), buffer_ideal.v_s(x - jfet.vto)];
+            let i_d = jfet.i_d(x - v_s, v_dd - v_s);
+            assert!((v_s - i_d*r_s).abs() < 1e-4);
+            assert!(v_s - v_s_ideal > 1e-3);
+        }
+    }
 }
\ No newline at end of file
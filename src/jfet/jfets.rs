@@ -1,9 +1,57 @@
 use super::JFETModel;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub struct JFET2N5458;
-
 impl JFETModel for JFET2N5458
 {
     const BETA: f64 = 488.9e-6;
     const VTO: f64 = -2.882;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFET2N5457;
+impl JFETModel for JFET2N5457
+{
+    const BETA: f64 = 1.125e-3;
+    const VTO: f64 = -1.372;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFET2N5952;
+impl JFETModel for JFET2N5952
+{
+    const BETA: f64 = 1.109e-3;
+    const VTO: f64 = -2.021;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFET2N3819;
+impl JFETModel for JFET2N3819
+{
+    const BETA: f64 = 1.304e-3;
+    const VTO: f64 = -3.0;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFETJ201;
+impl JFETModel for JFETJ201
+{
+    const BETA: f64 = 1e-3;
+    const VTO: f64 = -0.8;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFETMPF102;
+impl JFETModel for JFETMPF102
+{
+    const BETA: f64 = 1e-3;
+    const VTO: f64 = -3.3;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct JFET2SK30A;
+impl JFETModel for JFET2SK30A
+{
+    const BETA: f64 = 2.6e-3;
+    const VTO: f64 = -1.2;
 }
\ No newline at end of file
@@ -2,4 +2,63 @@ pub trait JFETModel
 {
     const BETA: f64;
     const VTO: f64;
+    /// Channel-length modulation (1/Volt)
+    const LAMBDA: f64 = 0.0;
+}
+
+/// Parameters of a JFET model only known at runtime, e.g. imported from a SPICE model card.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JFETParam
+{
+    pub beta: f64,
+    pub vto: f64,
+    /// Channel-length modulation (1/Volt)
+    pub lambda: f64
+}
+
+impl JFETParam
+{
+    /// Drain current of an n-channel JFET by the SPICE (Shichman–Hodges) equations, given gate-source and non-negative drain-source voltages.
+    pub fn i_d(&self, v_gs: f64, v_ds: f64) -> f64
+    {
+        let v_gst = v_gs - self.vto;
+        if v_gst <= 0.0
+        {
+            0.0
+        }
+        else if v_ds < v_gst
+        {
+            self.beta*v_ds*(2.0*v_gst - v_ds)*(1.0 + self.lambda*v_ds)
+        }
+        else
+        {
+            self.beta*v_gst*v_gst*(1.0 + self.lambda*v_ds)
+        }
+    }
+}
+
+/// A JFET model, either a preset implementing `JFETModel` or a `JFETParam`.
+pub trait JFETModelParam
+{
+    fn jfet(&self) -> JFETParam;
+}
+impl<M> JFETModelParam for M
+where
+    M: JFETModel
+{
+    fn jfet(&self) -> JFETParam
+    {
+        JFETParam {
+            beta: M::BETA,
+            vto: M::VTO,
+            lambda: M::LAMBDA
+        }
+    }
+}
+impl JFETModelParam for JFETParam
+{
+    fn jfet(&self) -> JFETParam
+    {
+        *self
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 #![cfg_attr(not(test), no_std)]
 #![allow(internal_features)]
+#![allow(incomplete_features)]
 #![feature(allocator_api)]
 #![feature(btreemap_alloc)]
 #![feature(btree_cursors)]
@@ -23,17 +24,30 @@ moddef::moddef!(
         pentode for cfg(feature = "tubes"),
         triode for cfg(feature = "tubes"),
 
+        adaptive_table for cfg(feature = "alloc"),
         atanmoid,
+        bias_point for cfg(feature = "tubes"),
         cache_table for cfg(feature = "alloc"),
         cache_tree for cfg(feature = "alloc"),
         erfmoid for cfg(feature = "libm"),
+        hermite_table for cfg(feature = "alloc"),
         linmoid,
+        noise for cfg(feature = "tubes"),
+        plate_curve for cfg(all(feature = "tubes", feature = "alloc")),
+        power_supply for cfg(feature = "tubes"),
+        push_pull for cfg(feature = "tubes"),
         pythmoid,
+        rtf_filter for cfg(feature = "tubes"),
         sinh_atanmoid,
         soft_exp for cfg(feature = "soft_exp"),
-        tanh
+        tanh,
+        tolerance for cfg(feature = "tubes"),
+        tone_stack for cfg(feature = "tubes")
     },
     pub mod {
+        fit for cfg(feature = "fit"),
+        spice for cfg(feature = "spice"),
+        thermal for cfg(feature = "diodes"),
         tubes for cfg(feature = "tubes")
     },
     mod {
@@ -78,6 +92,82 @@ where
     x.max(F::zero()) + (-x.abs()).exp().ln_1p()
 }
 
+/// Finds the root of an increasing function `f` within `bracket`, starting at `x`.
+///
+/// Takes Newton steps with a forward-difference derivative, falling back to bisection whenever a step leaves the bracket or spans more
+/// than half of it. Stops early once a step falls below the floating-point resolution of `x`.
+#[cfg(feature = "tubes")]
+fn newton_bracketed<F>(mut f: impl FnMut(F) -> F, x: F, mut bracket: [F; 2], delta: F, iterations: usize) -> F
+where
+    F: Float
+{
+    let two = F::one() + F::one();
+
+    let mut x = x.max(bracket[0]).min(bracket[1]);
+    for _ in 0..iterations
+    {
+        let f_x = f(x);
+        if f_x > F::zero()
+        {
+            bracket[1] = x
+        }
+        else
+        {
+            bracket[0] = x
+        }
+        let df_dx = (f(x + delta) - f_x)/delta;
+        let x_prev = x;
+        x = x - f_x/df_dx;
+        if (x - x_prev).abs() <= x_prev.abs()*F::epsilon()
+        {
+            break
+        }
+        if !(x >= bracket[0] && x <= bracket[1] && (x - x_prev).abs()*two <= bracket[1] - bracket[0])
+        {
+            x = (bracket[0] + bracket[1])/two
+        }
+    }
+    x
+}
+
+/// Maps a polynomial in `s` onto one in `z⁻¹` with the bilinear transform, both in ascending powers.
+#[cfg(feature = "tubes")]
+fn bilinear<F, const N: usize>(rate: F, s: [F; N]) -> [F; N]
+where
+    F: Float
+{
+    let two_rate = rate + rate;
+
+    let mut z = [F::zero(); N];
+    let mut c = F::one();
+    for (k, s) in s.into_iter().enumerate()
+    {
+        // sᵏ → (2·rate)ᵏ(1 - z⁻¹)ᵏ(1 + z⁻¹)ᴺ⁻¹⁻ᵏ
+        let mut p = [F::zero(); N];
+        p[0] = F::one();
+        for j in 0..N - 1
+        {
+            for i in (1..=j + 1).rev()
+            {
+                p[i] = if j < k
+                {
+                    p[i] - p[i - 1]
+                }
+                else
+                {
+                    p[i] + p[i - 1]
+                };
+            }
+        }
+        for (z, p) in z.iter_mut().zip(p)
+        {
+            *z = *z + s*c*p;
+        }
+        c = c*two_rate;
+    }
+    z
+}
+
 #[cfg(feature = "diodes")]
 fn lambertw<F>(x_ln: F) -> F
 where
@@ -164,4 +254,4 @@ mod tests
         )
         .expect("Plot failed");
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,413 @@
+use num::Float;
+
+use crate::f;
+
+/// A small, seeded xorshift64* random number generator, so that noisy renders are reproducible without `std`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NoiseRng
+{
+    state: u64
+}
+
+impl NoiseRng
+{
+    pub fn new(seed: u64) -> Self
+    {
+        // SplitMix64 scrambles the seed, so that similar seeds give unrelated sequences and the state is never zero
+        let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        Self {
+            state: if z == 0 {0x9E3779B97F4A7C15} else {z}
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Uniformly distributed in `[0, 1)`.
+    pub fn uniform<F>(&mut self) -> F
+    where
+        F: Float
+    {
+        f!((self.next_u64() >> 11) as f64/(1u64 << 53) as f64)
+    }
+
+    /// Normally distributed with zero mean and unit variance, by the Box-Muller transform.
+    pub fn gaussian<F>(&mut self) -> F
+    where
+        F: Float
+    {
+        let u1 = F::one() - self.uniform::<F>();
+        let u2 = self.uniform::<F>();
+        (-(u1.ln() + u1.ln())).sqrt()*(f!(core::f64::consts::TAU)*u2).cos()
+    }
+}
+
+/// Sample rate of the pole and gain pairs of Paul Kellet's economy pink noise filter.
+const KELLET_RATE: f64 = 44100.0;
+const KELLET: [(f64, f64); 3] = [
+    (0.99765, 0.0990460),
+    (0.96300, 0.2965164),
+    (0.57000, 1.0526913)
+];
+
+/// Number of heater hum harmonics.
+pub const HUM_HARMONICS: usize = 4;
+
+/// Amplitudes of the noise sources of a tube, all referred to the grid. A zero amplitude disables the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TubeNoiseParam<F>
+where
+    F: Float
+{
+    /// Shot noise density, per square root of plate current (V/√(A·Hz))
+    pub shot: F,
+    /// RMS flicker noise when sampled at 44.1 kHz. The spectrum is the same at any other rate (Volt)
+    pub flicker: F,
+    /// Mains frequency of the heater supply (Hz)
+    pub f_mains: F,
+    /// Peak heater hum at each harmonic of the mains frequency (Volt)
+    pub hum: [F; HUM_HARMONICS],
+    /// Grid voltage rung up by each volt of signal hitting the tube (Volt/Volt)
+    pub microphonics: F,
+    /// Mechanical resonance frequency of the electrodes (Hz)
+    pub f_microphonic: F,
+    /// Quality factor of the mechanical resonance
+    pub q_microphonic: F,
+    pub seed: u64
+}
+
+impl<F> TubeNoiseParam<F>
+where
+    F: Float
+{
+    /// A quiet preamp tube with AC heaters on mains of frequency `f_mains`.
+    pub fn preamp(f_mains: F, seed: u64) -> Self
+    {
+        Self {
+            shot: f!(6.5e-6),
+            flicker: f!(10e-6),
+            f_mains,
+            hum: [f!(100e-6), f!(30e-6), f!(10e-6), f!(3e-6)],
+            microphonics: f!(1e-3),
+            f_microphonic: f!(2.5e3),
+            q_microphonic: f!(30.0),
+            seed
+        }
+    }
+}
+
+/// Shot, flicker, heater hum and microphonic noise of a tube, referred to its grid.
+///
+/// - Shot noise is white, with a power proportional to the plate current.
+/// - Flicker noise is pink, shaped by Paul Kellet's economy filter, with its poles moved to the same frequencies at any rate.
+/// - Heater hum is a sum of harmonics of the mains frequency.
+/// - Microphonics is a lightly damped resonator, rung by the signal hitting the tube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TubeNoise<F>
+where
+    F: Float
+{
+    param: TubeNoiseParam<F>,
+    rng: NoiseRng,
+    pink: [F; 3],
+    phase: F,
+    microphonic: [F; 2]
+}
+
+impl<F> TubeNoise<F>
+where
+    F: Float
+{
+    pub fn new(param: TubeNoiseParam<F>) -> Self
+    {
+        Self {
+            param,
+            rng: NoiseRng::new(param.seed),
+            pink: [F::zero(); 3],
+            phase: F::zero(),
+            microphonic: [F::zero(); 2]
+        }
+    }
+
+    pub fn param(&self) -> &TubeNoiseParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut TubeNoiseParam<F>
+    {
+        &mut self.param
+    }
+
+    /// Reseeds the generator and clears all state, so the next render repeats the previous one.
+    pub fn reset(&mut self)
+    {
+        *self = Self::new(self.param)
+    }
+
+    /// Advances by one sample, with plate current `i_p` and the signal `x` hitting the tube, returning the noise voltage at the grid.
+    pub fn noise(&mut self, rate: F, i_p: F, x: F) -> F
+    {
+        let TubeNoiseParam {shot, flicker, f_mains, hum, microphonics, f_microphonic, q_microphonic, ..} = self.param;
+
+        let zero = F::zero();
+        let one = F::one();
+        let tau = f!(core::f64::consts::TAU);
+
+        let mut v = zero;
+
+        if shot != zero
+        {
+            // White noise sampled at `rate` spreads its power over the band up to half the rate
+            v = v + shot*(i_p.max(zero)*rate/(one + one)).sqrt()*self.rng.gaussian::<F>();
+        }
+
+        if flicker != zero
+        {
+            // Kellet's coefficients are for 44.1 kHz. Each pole keeps its frequency and its DC gain, and the white noise driving them
+            // keeps its density.
+            let ratio = f!(KELLET_RATE)/rate;
+            let w = self.rng.gaussian::<F>()/ratio.sqrt();
+            for (pink, (a, b)) in self.pink.iter_mut()
+                .zip(KELLET)
+            {
+                let a_ref = f!(a);
+                let a = a_ref.powf(ratio);
+                *pink = a**pink + w*f!(b)*(one - a)/(one - a_ref);
+            }
+            // Normalized to roughly unit RMS
+            v = v + flicker*(self.pink[0] + self.pink[1] + self.pink[2] + w*f!(0.1848))*f!(0.3);
+        }
+
+        if hum.iter().any(|&hum| hum != zero)
+        {
+            self.phase = self.phase + f_mains/rate;
+            self.phase = self.phase - self.phase.floor();
+            let mut k = zero;
+            for hum in hum
+            {
+                k = k + one;
+                v = v + hum*(tau*k*self.phase).sin();
+            }
+        }
+
+        if microphonics != zero
+        {
+            // Two-pole resonator, normalized to unity gain at resonance
+            let omega = tau*f_microphonic/rate;
+            let r = (-omega/(q_microphonic + q_microphonic)).exp();
+            let a1 = (r + r)*omega.cos();
+            let a2 = -r*r;
+            let g = (one - r)*(one - (r + r)*(omega + omega).cos() + r*r).sqrt();
+            let y = a1*self.microphonic[0] + a2*self.microphonic[1] + g*microphonics*x;
+            self.microphonic = [y, self.microphonic[0]];
+            v = v + y;
+        }
+
+        v
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::{f32::consts::TAU, ops::Range};
+
+    use crate::{tubes::{Tube12AX7, Tube6L6CG}, Pentode, PentodeClassA, Triode, TriodeClassA};
+
+    use super::*;
+
+    const RATE: f32 = 48000.0;
+
+    fn silent(seed: u64) -> TubeNoiseParam<f32>
+    {
+        TubeNoiseParam {
+            shot: 0.0,
+            flicker: 0.0,
+            f_mains: 50.0,
+            hum: [0.0; HUM_HARMONICS],
+            microphonics: 0.0,
+            f_microphonic: 2.5e3,
+            q_microphonic: 30.0,
+            seed
+        }
+    }
+
+    fn rms(x: impl Iterator<Item = f32>) -> f32
+    {
+        let (sum, n) = x.fold((0.0, 0), |(sum, n), x| (sum + x*x, n + 1));
+        (sum/n as f32).sqrt()
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = 0.0..0.04;
+        // One sample per plotted point
+        const RATE: f32 = 512.0/(RANGE.end - RANGE.start);
+
+        let mut n0 = TubeNoise::new(TubeNoiseParam {
+            shot: 0.1,
+            ..silent(0)
+        });
+        let mut n1 = TubeNoise::new(TubeNoiseParam {
+            flicker: 0.1,
+            ..silent(1)
+        });
+        let mut n2 = TubeNoise::new(TubeNoiseParam {
+            hum: [0.1, 0.03, 0.01, 0.003],
+            ..silent(2)
+        });
+        let mut n3 = TubeNoise::new(TubeNoiseParam {
+            microphonics: 5.0,
+            f_microphonic: 500.0,
+            q_microphonic: 10.0,
+            ..silent(3)
+        });
+
+        // A click hits the tube at the start, ringing the microphonic resonance
+        crate::tests::plot(
+            "TubeNoise",
+            RANGE,
+            |t| {
+                let x = if t == 0.0 {1.0} else {0.0};
+                [
+                    n0.noise(RATE, 1e-4, x) + 0.3,
+                    n1.noise(RATE, 1e-4, x) + 0.1,
+                    n2.noise(RATE, 1e-4, x) - 0.1,
+                    n3.noise(RATE, 1e-4, x) - 0.3
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn reproducible()
+    {
+        const N: usize = 1024;
+
+        let render = |seed| {
+            let mut triode = Triode::<_, Tube12AX7, (), (), (), _>::new(
+                TriodeClassA {
+                    r_i: 1e3,
+                    r_p: 100e3,
+                    v_pp: 300.0,
+                    v_c: 1.5
+                },
+                Tube12AX7,
+                ()
+            );
+            triode.set_noise(Some(TubeNoise::new(TubeNoiseParam::preamp(50.0, seed))));
+            core::array::from_fn::<_, N, _>(|n| triode.saturate(RATE, 0.1*(TAU*440.0*n as f32/RATE).sin()))
+        };
+
+        assert_eq!(render(1), render(1));
+        assert_ne!(render(1), render(2));
+
+        let mut noise = TubeNoise::new(TubeNoiseParam::preamp(60.0, 3));
+        let a: [f32; N] = core::array::from_fn(|_| noise.noise(RATE, 1e-3, 0.0));
+        noise.reset();
+        let b: [f32; N] = core::array::from_fn(|_| noise.noise(RATE, 1e-3, 0.0));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn sources()
+    {
+        const N: usize = 48000;
+
+        // Shot noise power follows the plate current
+        let mut shot = TubeNoise::new(TubeNoiseParam {
+            shot: 1.0,
+            ..silent(0)
+        });
+        let low = rms((0..N).map(|_| shot.noise(RATE, 1e-4, 0.0)));
+        let high = rms((0..N).map(|_| shot.noise(RATE, 4e-4, 0.0)));
+        assert!((high/low - 2.0).abs() < 0.1);
+
+        // Flicker noise has more energy in its slow drift than in its sample-to-sample changes, unlike white noise
+        let mut flicker = TubeNoise::new(TubeNoiseParam {
+            flicker: 1.0,
+            ..silent(0)
+        });
+        let pink: [f32; N] = core::array::from_fn(|_| flicker.noise(RATE, 0.0, 0.0));
+        let diff = rms(pink.windows(2).map(|w| w[1] - w[0]));
+        assert!(rms(pink.into_iter()) > 1.5*diff);
+
+        // Hum lies at the mains frequency, not beside it
+        let mut hum = TubeNoise::new(TubeNoiseParam {
+            hum: [1.0, 0.0, 0.0, 0.0],
+            ..silent(0)
+        });
+        let y: [f32; N] = core::array::from_fn(|_| hum.noise(RATE, 0.0, 0.0));
+        let correlation = |freq: f32| y.iter()
+            .enumerate()
+            .map(|(n, y)| y*(TAU*freq*n as f32/RATE).sin())
+            .sum::<f32>()
+            .abs()/N as f32;
+        assert!(correlation(50.0) > 0.4);
+        assert!(correlation(70.0) < 0.01);
+
+        // Microphonics is silent without a signal, and rings on after one
+        let mut microphonic = TubeNoise::new(TubeNoiseParam {
+            microphonics: 1.0,
+            ..silent(0)
+        });
+        assert_eq!(rms((0..N).map(|_| microphonic.noise(RATE, 1e-3, 0.0))), 0.0);
+        microphonic.noise(RATE, 1e-3, 1.0);
+        assert!(rms((0..480).map(|_| microphonic.noise(RATE, 1e-3, 0.0))) > 0.0);
+
+        // Noise also reaches the plate of a pentode
+        let mut quiet = Pentode::<_, Tube6L6CG, (), (), (), _>::new(
+            PentodeClassA {
+                r_i: 1e3,
+                r_p: 3.4e3,
+                v_pp: 400.0,
+                v_g2: 400.0,
+                v_c: 10.0
+            },
+            Tube6L6CG,
+            ()
+        );
+        let mut noisy = quiet;
+        noisy.set_noise(Some(TubeNoise::new(TubeNoiseParam {
+            hum: [0.1, 0.0, 0.0, 0.0],
+            ..silent(0)
+        })));
+        assert!(rms((0..N).map(|_| noisy.saturate(RATE, 0.0) - quiet.saturate(RATE, 0.0))) > 0.1);
+    }
+
+    #[test]
+    fn rate()
+    {
+        // Averaged over 10 ms, which no audible difference should depend on, the noise is the same at any sample rate
+        let blocks = |param: TubeNoiseParam<f32>, rate: f32| {
+            const BLOCKS: usize = 400;
+
+            let len = (rate*10e-3) as usize;
+            let mut noise = TubeNoise::new(param);
+            rms((0..BLOCKS).map(|_| (0..len).map(|_| noise.noise(rate, 1e-3, 0.0)).sum::<f32>()/len as f32))
+        };
+        for param in [
+            TubeNoiseParam {
+                shot: 1.0,
+                ..silent(0)
+            },
+            TubeNoiseParam {
+                flicker: 1.0,
+                ..silent(0)
+            }
+        ]
+        {
+            let [low, high] = [44100.0, 96000.0].map(|rate| blocks(param, rate));
+            assert!((high/low - 1.0).abs() < 0.1);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, BiasPoint};
+
+use super::{PentodeClassA, PentodeModelParam};
+
+impl<F> PentodeClassA<F>
+where
+    F: FilterFloat
+{
+    /// Solves the quiescent point of the stage when self-biased by the cathode resistor `r_k`.
+    ///
+    /// The supply and screen voltages are taken relative to ground. Both the plate and the screen current flow through the cathode
+    /// resistor.
+    pub fn bias<M>(&self, model: &M, r_k: F) -> BiasPoint<F>
+    where
+        M: PentodeModelParam
+    {
+        const NEWTON: usize = 32;
+        const DELTA: f64 = 1e-6;
+
+        let PentodeClassA {r_p, v_g2, v_pp, ..} = *self;
+        let model = model.pentode();
+
+        // Plate-cathode voltage, plate current and cathode current, for a given cathode voltage
+        let v_p_i_p_i_k = |v_k: F| {
+            let v_pk = v_pp - v_k;
+            let v_g2k = v_g2 - v_k;
+            // The plate current rises with the plate voltage while the load current falls, so g is increasing
+            let g = |v_p: F| model.i_p(-v_k, v_g2k, v_p) - (v_pk - v_p)/r_p;
+            let v_p = crate::newton_bracketed(g, v_pk, [F::zero(), v_pk.max(F::zero())], v_pp*f!(DELTA), NEWTON);
+            let i_p = (v_pk - v_p)/r_p;
+            [v_p, i_p, i_p + model.i_g2(-v_k, v_g2k)]
+        };
+
+        // Raising the cathode starves the pentode, so the current falls and f is increasing
+        let f = |v_k: F| {
+            let [_, _, i_k] = v_p_i_p_i_k(v_k);
+            v_k - r_k*i_k
+        };
+        let v_k = crate::newton_bracketed(f, F::zero(), [F::zero(), v_pp.min(v_g2)], v_pp*f!(DELTA), NEWTON);
+        let [v_p, i_p, i_k] = v_p_i_p_i_k(v_k);
+
+        BiasPoint {
+            v_k,
+            v_p: v_k + v_p,
+            i_p,
+            i_k,
+            p_p: v_p*i_p,
+            p_max: f!(model.triode.p_max)
+        }
+    }
+
+    /// The stage at the quiescent point `bias`, as `Pentode` takes it, with the supply and screen voltages relative to the cathode.
+    pub fn with_bias(self, bias: &BiasPoint<F>) -> Self
+    {
+        Self {
+            v_pp: self.v_pp - bias.v_k,
+            v_g2: self.v_g2 - bias.v_k,
+            v_c: bias.v_k,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::{tubes::{Tube6550, Tube6L6CG}, Pentode};
+
+    use super::*;
+
+    fn param() -> PentodeClassA<f32>
+    {
+        PentodeClassA {
+            r_i: 1e3,
+            r_p: 100.0,
+            v_g2: 350.0,
+            v_pp: 360.0,
+            v_c: 0.0
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        // Cathode resistor in Ω
+        const RANGE: Range<f32> = 50.0..1000.0;
+
+        // Cathode voltage, plate voltage in decavolts, plate current in mA and dissipation in W
+        crate::tests::plot(
+            "PentodeBias",
+            RANGE,
+            |r_k| {
+                let bias = param().bias(&Tube6L6CG, r_k);
+                [bias.v_k, bias.v_p*1e-1, bias.i_p*1e3, bias.p_p]
+            }
+        )
+    }
+
+    #[test]
+    fn quiescent()
+    {
+        const R_K: f32 = 250.0;
+
+        let supply = param();
+        let bias = supply.bias(&Tube6L6CG, R_K);
+        let param = supply.with_bias(&bias);
+
+        // Only the quiescent point is solved, the supply is left as it is
+        assert_eq!(supply.bias(&Tube6L6CG, R_K), bias);
+
+        // The screen current also flows through the cathode resistor
+        assert_eq!(param.v_c, bias.v_k);
+        assert_eq!([param.v_pp, param.v_g2], [supply.v_pp - bias.v_k, supply.v_g2 - bias.v_k]);
+        assert!(bias.i_k > bias.i_p);
+        assert!((bias.v_k - R_K*bias.i_k).abs() < 1e-3*bias.v_k);
+        assert!((supply.v_pp - bias.v_p - param.r_p*bias.i_p).abs() < 1e-3*supply.v_pp);
+
+        // A stage built from the biased parameters idles at the same point
+        let pentode = Pentode::<_, Tube6L6CG, (), (), (), _>::new(param, Tube6L6CG, ());
+        assert!((pentode.i_p() - bias.i_p).abs() < 1e-3*bias.i_p);
+
+        // Starving the cathode resistor runs the plate hotter
+        let hot = PentodeClassA {
+            v_g2: 450.0,
+            v_pp: 460.0,
+            ..supply
+        }.bias(&Tube6L6CG, 0.1*R_K);
+        assert!(hot.p_p > bias.p_p);
+        assert!(hot.is_overdissipated());
+        assert!(!supply.bias(&Tube6550, 4.0*R_K).is_overdissipated());
+    }
+}
\ No newline at end of file
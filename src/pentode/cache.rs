@@ -3,46 +3,95 @@ use alloc::alloc::{Allocator, Global};
 
 use real_time_fir_iir_filters::param::FilterFloat;
 
-use crate::CacheTable;
+use crate::{f, CacheTable, HermiteTable, Interpolation};
 
-use super::{calc::PentodeCalc, PentodeClassA, PentodeModel};
+use super::{calc::PentodeCalc, PentodeClassA, PentodeModelParam, PentodeParam};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 struct PentodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     param: PentodeClassA<F>,
+    model: Option<PentodeParam>,
     marker: PhantomData<M>
 }
 impl<F, M> FnOnce<(F,)> for PentodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     type Output = [F; 2];
 
     extern "rust-call" fn call_once(mut self, (vg,): (F,)) -> Self::Output
     {
-        PentodeCalc::<F, M>::vp_a(&mut self.param, vg)
+        self.call_mut((vg,))
     }
 }
 impl<F, M> FnMut<(F,)> for PentodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     extern "rust-call" fn call_mut(&mut self, (vg,): (F,)) -> Self::Output
     {
-        PentodeCalc::<F, M>::vp_a(&mut self.param, vg)
+        match self.model
+        {
+            Some(model) => PentodeCalc::<F, PentodeParam>::vp_a(&mut self.param, &model, vg),
+            None => [F::nan(); 2]
+        }
+    }
+}
+/// Relative change of the supply voltage before a cache following a `PowerSupply` rebuilds its table.
+const V_PP_HYSTERESIS: f64 = 0.02;
+
+/// A table of the plate voltage, filled by a `PentodeCacheFunc` and refreshed whenever the model changes.
+trait PentodeTable<F, M>
+where
+    F: FilterFloat,
+    M: PentodeModelParam
+{
+    fn cache_func(&self) -> &PentodeCacheFunc<F, M>;
+    fn cache_func_mut(&mut self) -> &mut PentodeCacheFunc<F, M>;
+
+    fn set_model(&mut self, model: &M)
+    {
+        let model = Some(model.pentode());
+        if self.cache_func().model != model
+        {
+            self.cache_func_mut().model = model;
+        }
+    }
+
+    /// Rebuilds the table lazily, only once the supply has left the band around the voltage the table was built for.
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        let v_pp_table = self.cache_func().param.v_pp;
+        (v_pp - v_pp_table).abs() > f!(V_PP_HYSTERESIS)*v_pp_table.abs()
+            && PentodeCalc::<F, M>::track_v_pp(&mut self.cache_func_mut().param, v_pp)
+    }
+}
+impl<F, M, A> PentodeTable<F, M> for CacheTable<F, 2, PentodeCacheFunc<F, M>, A>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    A: Allocator + Clone
+{
+    fn cache_func(&self) -> &PentodeCacheFunc<F, M>
+    {
+        self.func()
+    }
+    fn cache_func_mut(&mut self) -> &mut PentodeCacheFunc<F, M>
+    {
+        self.func_mut()
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PentodeCache<F, M, A = Global>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     A: Allocator + Clone
 {
     cache: CacheTable<F, 2, PentodeCacheFunc<F, M>, A>
@@ -50,7 +99,7 @@ where
 impl<F, M> PentodeCache<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     pub fn new(param: PentodeClassA<F>, range: Range<F>, resolution: usize) -> Self
     {
@@ -60,7 +109,7 @@ where
 impl<F, M, A> PentodeCache<F, M, A>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     A: Allocator + Clone
 {
     pub fn new_in(param: PentodeClassA<F>, range: Range<F>, resolution: usize, alloc: A) -> Self
@@ -69,6 +118,7 @@ where
             cache: CacheTable::new_in(
                 PentodeCacheFunc {
                     param,
+                    model: None,
                     marker: PhantomData
                 },
                 range,
@@ -77,23 +127,173 @@ where
             )
         }
     }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self
+    {
+        self.set_interpolation(interpolation);
+        self
+    }
+
+    pub fn interpolation(&self) -> Interpolation
+    {
+        self.cache.interpolation()
+    }
+    pub fn set_interpolation(&mut self, interpolation: Interpolation)
+    {
+        self.cache.set_interpolation(interpolation)
+    }
+
+    /// The largest deviation of the plate voltage and the gain from the exact solution, checked at `oversampling` points inside each
+    /// cell of the table.
+    pub fn worst_case_error(&mut self, model: &M, oversampling: usize) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        self.cache.worst_case_error(oversampling)
+    }
 }
 impl<F, M, A> PentodeCalc<F, M> for PentodeCache<F, M, A>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     A: Allocator + Clone
 {
     fn param(&self) -> &PentodeClassA<F>
     {
-        &self.cache.func().param
+        &self.cache.cache_func().param
     }
     fn param_mut(&mut self) -> &mut PentodeClassA<F>
     {
-        &mut self.cache.func_mut().param
+        &mut self.cache.cache_func_mut().param
     }
-    fn vp_a(&mut self, vg: F) -> [F; 2]
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
     {
+        self.cache.set_model(model);
         self.cache.saturate(vg)
     }
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        self.cache.track_v_pp(v_pp)
+    }
+}
+
+/// Plate voltage and its derivative with respect to the grid voltage, which is the negated gain.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct PentodeSlopeFunc<F, M>(PentodeCacheFunc<F, M>)
+where
+    F: FilterFloat,
+    M: PentodeModelParam;
+impl<F, M> FnOnce<(F,)> for PentodeSlopeFunc<F, M>
+where
+    F: FilterFloat,
+    M: PentodeModelParam
+{
+    type Output = [F; 2];
+
+    extern "rust-call" fn call_once(mut self, (vg,): (F,)) -> Self::Output
+    {
+        self.call_mut((vg,))
+    }
+}
+impl<F, M> FnMut<(F,)> for PentodeSlopeFunc<F, M>
+where
+    F: FilterFloat,
+    M: PentodeModelParam
+{
+    extern "rust-call" fn call_mut(&mut self, (vg,): (F,)) -> Self::Output
+    {
+        let [vp, a] = self.0.call_mut((vg,));
+        [vp, -a]
+    }
+}
+impl<F, M, A> PentodeTable<F, M> for HermiteTable<F, PentodeSlopeFunc<F, M>, A>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    A: Allocator + Clone
+{
+    fn cache_func(&self) -> &PentodeCacheFunc<F, M>
+    {
+        &self.func().0
+    }
+    fn cache_func_mut(&mut self) -> &mut PentodeCacheFunc<F, M>
+    {
+        &mut self.func_mut().0
+    }
+}
+/// A cache of the plate voltage interpolated by a `HermiteTable`, using the gain of the model as its slope.
+///
+/// Both the plate voltage and the gain are continuous between the cells of the table, so the Miller effect does not step as the grid
+/// voltage sweeps across them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PentodeHermiteCache<F, M, A = Global>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    A: Allocator + Clone
+{
+    cache: HermiteTable<F, PentodeSlopeFunc<F, M>, A>
+}
+impl<F, M> PentodeHermiteCache<F, M>
+where
+    F: FilterFloat,
+    M: PentodeModelParam
+{
+    pub fn new(param: PentodeClassA<F>, range: Range<F>, resolution: usize) -> Self
+    {
+        Self::new_in(param, range, resolution, Global)
+    }
+}
+impl<F, M, A> PentodeHermiteCache<F, M, A>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    A: Allocator + Clone
+{
+    pub fn new_in(param: PentodeClassA<F>, range: Range<F>, resolution: usize, alloc: A) -> Self
+    {
+        Self {
+            cache: HermiteTable::new_in(
+                PentodeSlopeFunc(PentodeCacheFunc {
+                    param,
+                    model: None,
+                    marker: PhantomData
+                }),
+                range,
+                resolution,
+                alloc
+            )
+        }
+    }
+
+    /// As `PentodeCache::worst_case_error`.
+    pub fn worst_case_error(&mut self, model: &M, oversampling: usize) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        self.cache.worst_case_error(oversampling)
+    }
+}
+impl<F, M, A> PentodeCalc<F, M> for PentodeHermiteCache<F, M, A>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    A: Allocator + Clone
+{
+    fn param(&self) -> &PentodeClassA<F>
+    {
+        &self.cache.cache_func().param
+    }
+    fn param_mut(&mut self) -> &mut PentodeClassA<F>
+    {
+        &mut self.cache.cache_func_mut().param
+    }
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        let [vp, dvp] = self.cache.saturate(vg);
+        [vp, -dvp]
+    }
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        self.cache.track_v_pp(v_pp)
+    }
 }
\ No newline at end of file
@@ -1,13 +1,13 @@
 use real_time_fir_iir_filters::param::FilterFloat;
 
-use super::{PentodeClassA, PentodeModel};
+use super::{PentodeClassA, PentodeModelParam};
 
 use crate::f;
 
 pub trait PentodeCalc<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     fn reset(&mut self)
     {
@@ -15,12 +15,27 @@ where
     }
     fn param(&self) -> &PentodeClassA<F>;
     fn param_mut(&mut self) -> &mut PentodeClassA<F>;
-    fn vp_a(&mut self, vg: F) -> [F; 2];
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2];
+
+    /// Follows a supply voltage changing while running, returning whether the plate solve has changed.
+    ///
+    /// The screen is fed from the same supply through a fixed drop, so it moves along with the plate supply.
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        let param = self.param_mut();
+        let changed = param.v_pp != v_pp;
+        if changed
+        {
+            param.v_g2 = param.v_g2 + v_pp - param.v_pp;
+            param.v_pp = v_pp;
+        }
+        changed
+    }
 }
 impl<F, M> PentodeCalc<F, M> for PentodeClassA<F>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     fn param(&self) -> &PentodeClassA<F>
     {
@@ -30,18 +45,20 @@ where
     {
         self
     }
-    fn vp_a(&mut self, vg: F) -> [F; 2]
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
     {
         let PentodeClassA {r_i: _, r_p: rp, v_g2: vg2, v_pp: vpp, v_c: _} = *self;
         let two_rp = rp + rp;
         let one = F::one();
         let zero = F::zero();
 
-        let mu_inv = f!(1.0/M::MU);
-        let kp = f!(M::K_P);
-        let kvb = f!(M::K_VB);
-        let kg1 = f!(M::K_G1);
-        let ex = f!(M::EX);
+        let model = model.triode();
+
+        let mu_inv = f!(1.0/model.mu);
+        let kp = f!(model.k_p);
+        let kvb = f!(model.k_vb);
+        let kg1 = f!(model.k_g1);
+        let ex = f!(model.ex);
 
         let vg2_d_kp = vg2/kp;
         let c = kp*(mu_inv + vg/vg2);
@@ -62,7 +79,10 @@ where
             }
             vp = vp.max(zero).min(vpp);
 
-            const NEWTON: usize = 2;
+            const NEWTON: usize = 16;
+
+            // f is decreasing in vp, so a Newton step leaving the bracket falls back to bisection
+            let mut bracket = [zero, vpp];
 
             for _ in 0..NEWTON
             {
@@ -71,11 +91,27 @@ where
                 let f = vpp_d_rp - vp/two_rp - b*vp.atan2(kvb);
                 let df_dvp = -rp_inv - b/(vp*vp_d_kvb + kvb);
 
+                if f > zero
+                {
+                    bracket[0] = vp
+                }
+                else
+                {
+                    bracket[1] = vp
+                }
+
                 let delta = f/df_dvp;
+                if delta.abs() <= vpp*F::epsilon()
+                {
+                    break
+                }
                 vp = vp - delta;
+                if !(vp >= bracket[0] && vp <= bracket[1])
+                {
+                    vp = (bracket[0] + bracket[1])/(one + one)
+                }
             }
 
-            vp = vp.max(zero).min(vpp);
             let vp_d_kvb = vp/kvb;
 
             let df_dvp = -rp_inv - b/(vp*vp_d_kvb + kvb);
@@ -0,0 +1,86 @@
+use core::ops::Range;
+
+use alloc::vec::Vec;
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, LoadLine, PlateCurve};
+
+use super::{PentodeClassA, PentodeModelParam, PentodeParam};
+
+impl PentodeParam
+{
+    /// Plate curves at screen voltage `v_g2` for each grid-cathode voltage in `v_g`, each sampled at `n` evenly spaced plate voltages in `v_p`.
+    pub fn plate_curves(&self, v_g: &[f64], v_g2: f64, v_p: Range<f64>, n: usize) -> Vec<PlateCurve>
+    {
+        v_g.iter()
+            .map(|&v_g| PlateCurve::new(v_g, v_p.clone(), n, |v_p| self.i_p(v_g, v_g2, v_p)))
+            .collect()
+    }
+}
+
+impl<F> PentodeClassA<F>
+where
+    F: FilterFloat
+{
+    /// The plate resistor's load line, intersecting the plate curve at grid-cathode voltage `v_g`.
+    ///
+    /// With `v_g = -v_c`, this is the quiescent operating point. Found by bisection on `PentodeParam::i_p` rather than by the solver
+    /// `Pentode` runs each sample.
+    pub fn load_line<M>(&self, model: &M, v_g: f64) -> LoadLine
+    where
+        M: PentodeModelParam
+    {
+        let model = model.pentode();
+        let v_g2 = f!(self.v_g2; f64);
+        LoadLine::new(f!(self.v_pp; f64), f!(self.r_p; f64), v_g, |v_p| model.i_p(v_g, v_g2, v_p))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{tubes::{Tube6550, Tube6L6CG, TubeKT88}, write_plate_curves_csv};
+
+    use super::{super::PentodeCalc, *};
+
+    #[test]
+    fn plate_curves()
+    {
+        const N: usize = 256;
+        const V_G: [f64; 6] = [0.0, -5.0, -10.0, -15.0, -20.0, -25.0];
+
+        let curves = Tube6L6CG.pentode().plate_curves(&V_G, 250.0, 0.0..500.0, N);
+
+        let mut csv = String::new();
+        write_plate_curves_csv(&curves, &mut csv).unwrap();
+        assert_eq!(csv.lines().count(), 1 + V_G.len()*N);
+
+        crate::plot::plot_curves(
+            "Plate curves of Tube6L6CG",
+            "plots/pentode_plate_curves.png",
+            [core::array::from_fn(|i| curves[0].v_p[i] as f32); V_G.len()],
+            core::array::from_fn(|j| core::array::from_fn::<_, N, _>(|i| curves[j].i_p[i] as f32*1e3))
+        )
+        .expect("Plot failed")
+    }
+
+    #[test]
+    fn load_line()
+    {
+        let mut param = PentodeClassA {
+            r_i: 1e3,
+            r_p: 5e3,
+            v_g2: 250.0,
+            v_pp: 350.0,
+            v_c: 15.0
+        };
+
+        for model in [Tube6L6CG.pentode(), Tube6550.pentode(), TubeKT88.pentode()]
+        {
+            let load_line = param.load_line(&model, -param.v_c);
+            let [v_p, _] = param.vp_a(&model, -param.v_c);
+            assert!((load_line.v_p - v_p).abs() < 1e-3*param.v_pp);
+            assert!((load_line.i_p - model.i_p(load_line.v_g, param.v_g2, load_line.v_p)).abs() < 1e-6*load_line.i_max);
+        }
+    }
+}
\ No newline at end of file
@@ -1,13 +1,13 @@
-use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, param::{FilterFloat, RC}, rtf::{Rtf, StaticRtf}};
+use real_time_fir_iir_filters::{conf::LowPass, filters::iir::{first::FirstOrderRCFilter, second::SecondOrderRLCFilter}, param::{FilterFloat, RC, RLC}, rtf::{Rtf, StaticRtf}};
 
-use super::{PentodeClassA, PentodeModel};
+use super::{PentodeClassA, PentodeModelParam};
 
-use crate::f;
+use crate::{f, RtfFilter};
 
 pub trait PentodeCathodeFilter<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     type Param;
 
@@ -21,7 +21,7 @@ where
 impl<F, M> PentodeCathodeFilter<F, M> for ()
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
     type Param = ();
 
@@ -47,7 +47,7 @@ where
 impl<F, M> PentodeCathodeFilter<F, M> for FirstOrderRCFilter<LowPass, F, RC<F>>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     Self: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
 {
     type Param = RC<F>;
@@ -76,44 +76,44 @@ where
 pub trait PentodeFilter<F, M>
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
-    fn new_input_filter(r_i: F) -> Self;
-    fn new_output_filter(r_p: F) -> Self;
+    fn new_input_filter(model: &M, r_i: F) -> Self;
+    fn new_output_filter(model: &M, r_p: F) -> Self;
 
-    fn update_miller_effect_input(&mut self, miller_effect: F);
-    fn update_miller_effect_output(&mut self, miller_effect: F);
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F);
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F);
 
-    fn vg(&mut self, param: PentodeClassA<F>, rate: F, x: F) -> F;
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, rate: F, x: F) -> F;
     fn y(&mut self, rate: F, y: F) -> F;
 }
 impl<F, M> PentodeFilter<F, M> for ()
 where
     F: FilterFloat,
-    M: PentodeModel
+    M: PentodeModelParam
 {
-    fn new_input_filter(_: F) -> Self
+    fn new_input_filter(_: &M, _: F) -> Self
     {
         
     }
-    fn new_output_filter(_: F) -> Self
+    fn new_output_filter(_: &M, _: F) -> Self
     {
         
     }
 
-    fn update_miller_effect_input(&mut self, _: F)
+    fn update_miller_effect_input(&mut self, _: &M, _: F)
     {
 
     }
-    fn update_miller_effect_output(&mut self, _: F)
+    fn update_miller_effect_output(&mut self, _: &M, _: F)
     {
 
     }
 
-    fn vg(&mut self, param: PentodeClassA<F>, _: F, x: F) -> F
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, _: F, x: F) -> F
     {
         let ri = param.r_i;
-        let rgi = f!(M::R_GI);
+        let rgi = f!(model.triode().r_gi);
 
         x*rgi/(rgi + ri) - param.v_c
     }
@@ -125,30 +125,34 @@ where
 impl<F, M> PentodeFilter<F, M> for FirstOrderRCFilter<LowPass, F, RC<F>>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     Self: Rtf<F = F, Outputs<F> = [F; 1]>
 {
-    fn new_input_filter(r_i: F) -> Self
+    fn new_input_filter(model: &M, r_i: F) -> Self
     {
-        FirstOrderRCFilter::new(RC {r: r_i, c: f!(M::C_CG + M::C_PG)})
+        let model = model.triode();
+        FirstOrderRCFilter::new(RC {r: r_i, c: f!(model.c_cg + model.c_pg)})
     }
-    fn new_output_filter(r_p: F) -> Self
+    fn new_output_filter(model: &M, r_p: F) -> Self
     {
-        FirstOrderRCFilter::new(RC {r: r_p, c: f!(M::C_CP + M::C_PG)})
+        let model = model.triode();
+        FirstOrderRCFilter::new(RC {r: r_p, c: f!(model.c_cp + model.c_pg)})
     }
 
-    fn update_miller_effect_input(&mut self, miller_effect: F)
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F)
     {
-        self.param.c = f!(M::C_CG) + f!(M::C_PG)*miller_effect;
+        let model = model.triode();
+        self.param.c = f!(model.c_cg) + f!(model.c_pg)*miller_effect;
     }
-    fn update_miller_effect_output(&mut self, miller_effect: F)
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F)
     {
-        self.param.c = f!(M::C_CP) + f!(M::C_PG)*miller_effect;
+        let model = model.triode();
+        self.param.c = f!(model.c_cp) + f!(model.c_pg)*miller_effect;
     }
 
-    fn vg(&mut self, param: PentodeClassA<F>, rate: F, x: F) -> F
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, rate: F, x: F) -> F
     {
-        let [vg] = self.filter(rate, PentodeFilter::<F, M>::vg(&mut (), param, rate, x));
+        let [vg] = self.filter(rate, PentodeFilter::<F, M>::vg(&mut (), model, param, rate, x));
         vg
     }
     fn y(&mut self, rate: F, y: F) -> F
@@ -156,4 +160,94 @@ where
         let [y] = self.filter(rate, PentodeFilter::<F, M>::y(&mut (), rate, y));
         y
     }
+}
+/// As for a [`Triode`](crate::Triode), the inductance starts at zero, and is set through
+/// [`Pentode::input_filter_mut`](crate::Pentode::input_filter_mut) or [`Pentode::output_filter_mut`](crate::Pentode::output_filter_mut).
+impl<F, M> PentodeFilter<F, M> for SecondOrderRLCFilter<LowPass, F, RLC<F>>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    Self: Rtf<F = F, Outputs<F> = [F; 1]>
+{
+    fn new_input_filter(model: &M, r_i: F) -> Self
+    {
+        let model = model.triode();
+        Self::new(RLC {r: r_i, l: F::zero(), c: f!(model.c_cg + model.c_pg)})
+    }
+    fn new_output_filter(model: &M, r_p: F) -> Self
+    {
+        let model = model.triode();
+        Self::new(RLC {r: r_p, l: F::zero(), c: f!(model.c_cp + model.c_pg)})
+    }
+
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F)
+    {
+        let model = model.triode();
+        self.param.c = f!(model.c_cg) + f!(model.c_pg)*miller_effect;
+    }
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F)
+    {
+        let model = model.triode();
+        self.param.c = f!(model.c_cp) + f!(model.c_pg)*miller_effect;
+    }
+
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, rate: F, x: F) -> F
+    {
+        let [vg] = self.filter(rate, PentodeFilter::<F, M>::vg(&mut (), model, param, rate, x));
+        vg
+    }
+    fn y(&mut self, rate: F, y: F) -> F
+    {
+        let [y] = self.filter(rate, PentodeFilter::<F, M>::y(&mut (), rate, y));
+        y
+    }
+}
+impl<F, M, T> PentodeFilter<F, M> for RtfFilter<T>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    T: Rtf<F = F, Outputs<F> = [F; 1]>
+{
+    fn new_input_filter(_: &M, _: F) -> Self
+    {
+        Self::default()
+    }
+    fn new_output_filter(_: &M, _: F) -> Self
+    {
+        Self::default()
+    }
+
+    fn update_miller_effect_input(&mut self, _: &M, _: F)
+    {
+
+    }
+    fn update_miller_effect_output(&mut self, _: &M, _: F)
+    {
+
+    }
+
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, rate: F, x: F) -> F
+    {
+        let x = match self.filter_mut()
+        {
+            Some(filter) => {
+                let [x] = filter.filter(rate, x);
+                x
+            },
+            None => x
+        };
+        PentodeFilter::<F, M>::vg(&mut (), model, param, rate, x)
+    }
+    fn y(&mut self, rate: F, y: F) -> F
+    {
+        let y = PentodeFilter::<F, M>::y(&mut (), rate, y);
+        match self.filter_mut()
+        {
+            Some(filter) => {
+                let [y] = filter.filter(rate, y);
+                y
+            },
+            None => y
+        }
+    }
 }
\ No newline at end of file
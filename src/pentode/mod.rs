@@ -1,12 +1,15 @@
 use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, param::FilterFloat};
 
-use crate::tubes::Tube6550;
+use crate::{tubes::Tube6550, PowerSupplyLoad, TubeNoise};
 
 moddef::moddef!(
     flat(pub) mod {
+        bias,
         cache for cfg(feature = "alloc"),
+        curves for cfg(feature = "alloc"),
         model,
-        param
+        param,
+        speaker_load
     },
     flat mod {
         calc,
@@ -20,7 +23,7 @@ macro_rules! decl {
         pub struct Pentode<F, M = Tube6550, FI = FirstOrderRCFilter<LowPass, F>, FO = FirstOrderRCFilter<LowPass, F>, FC = FirstOrderRCFilter<LowPass, F>, C = $calc>
         where
             F: FilterFloat,
-            M: PentodeModel,
+            M: PentodeModelParam,
             C: PentodeCalc<F, M>,
             FI: PentodeFilter<F, M>,
             FO: PentodeFilter<F, M>,
@@ -32,6 +35,9 @@ macro_rules! decl {
             cathode_filter: FC,
             miller_effect: F,
             offset: F,
+            i_p: F,
+            v_g: F,
+            noise: Option<TubeNoise<F>>,
             model: M
         }
     };
@@ -44,7 +50,7 @@ decl!(PentodeClassA<F>);
 impl<F, M, C, FI, FO, FC> Pentode<F, M, FI, FO, FC, C>
 where
     F: FilterFloat,
-    M: PentodeModel,
+    M: PentodeModelParam,
     C: PentodeCalc<F, M>,
     FI: PentodeFilter<F, M>,
     FO: PentodeFilter<F, M>,
@@ -53,8 +59,8 @@ where
     pub fn new(calc: C, model: M, cathode: FC::Param) -> Self
     {
         let param = calc.param();
-        let input_filter = FI::new_input_filter(param.r_i);
-        let output_filter = FO::new_output_filter(param.r_p);
+        let input_filter = FI::new_input_filter(&model, param.r_i);
+        let output_filter = FO::new_output_filter(&model, param.r_p);
         let cathode_filter = FC::new_cathode_filter(cathode);
         let mut pentode = Self {
             calc,
@@ -63,6 +69,9 @@ where
             cathode_filter,
             miller_effect: F::one(),
             offset: F::zero(),
+            i_p: F::zero(),
+            v_g: F::zero(),
+            noise: None,
             model,
         };
         pentode.calibrate();
@@ -77,6 +86,44 @@ where
     {
         self.calc.param_mut()
     }
+    pub fn model(&self) -> &M
+    {
+        &self.model
+    }
+    pub fn model_mut(&mut self) -> &mut M
+    {
+        &mut self.model
+    }
+    pub fn input_filter(&self) -> &FI
+    {
+        &self.input_filter
+    }
+    pub fn input_filter_mut(&mut self) -> &mut FI
+    {
+        &mut self.input_filter
+    }
+    pub fn output_filter(&self) -> &FO
+    {
+        &self.output_filter
+    }
+    /// Gives access to the output filter, e.g. to change the parameters of a [`SpeakerLoad`].
+    pub fn output_filter_mut(&mut self) -> &mut FO
+    {
+        &mut self.output_filter
+    }
+    pub fn noise(&self) -> Option<&TubeNoise<F>>
+    {
+        self.noise.as_ref()
+    }
+    pub fn noise_mut(&mut self) -> Option<&mut TubeNoise<F>>
+    {
+        self.noise.as_mut()
+    }
+    /// Adds noise sources at the grid, or removes them with `None`.
+    pub fn set_noise(&mut self, noise: Option<TubeNoise<F>>)
+    {
+        self.noise = noise
+    }
     pub fn param_cathode(&self) -> &FC::Param
     {
         self.cathode_filter.param_cathode()
@@ -88,7 +135,9 @@ where
 
     pub fn calibrate(&mut self)
     {
-        [self.offset, _] = self.calc.vp_a(-self.param().v_c);
+        self.v_g = -self.param().v_c;
+        [self.offset, _] = self.calc.vp_a(&self.model, self.v_g);
+        self.i_p = (self.param().v_pp - self.offset)/self.param().r_p;
     }
 
     pub fn saturate(&mut self, rate: F, x: F) -> F
@@ -101,16 +150,22 @@ where
         let param = *self.param();
 
         let mut vg = self.cathode_filter.vg_cathode(param, self.miller_effect, rate, x);
-        vg = self.input_filter.vg(param, rate, vg);
+        vg = self.input_filter.vg(&self.model, param, rate, vg);
+        if let Some(noise) = &mut self.noise
+        {
+            vg = vg + noise.noise(rate, self.i_p, x);
+        }
 
-        let [vp, a] = self.calc.vp_a(vg);
+        let [vp, a] = self.calc.vp_a(&self.model, vg);
+        self.v_g = vg;
 
         let y = vp - self.offset;
+        self.i_p = (param.v_pp - vp)/param.r_p;
 
         self.miller_effect = one + a.max(zero);
 
-        self.input_filter.update_miller_effect_input(self.miller_effect);
-        self.output_filter.update_miller_effect_output(self.miller_effect);
+        self.input_filter.update_miller_effect_input(&self.model, self.miller_effect);
+        self.output_filter.update_miller_effect_output(&self.model, self.miller_effect);
 
         self.output_filter.y(rate, y)
     }
@@ -123,6 +178,39 @@ where
     {
         self.offset
     }
+    /// Plate current drawn from the supply during the last sample.
+    pub fn i_p(&self) -> F
+    {
+        self.i_p
+    }
+    /// Screen current drawn from the supply during the last sample.
+    pub fn i_g2(&self) -> F
+    {
+        self.model.pentode().i_g2(self.v_g, self.param().v_g2)
+    }
+}
+
+impl<F, M, FI, FO, FC, C> PowerSupplyLoad<F> for Pentode<F, M, FI, FO, FC, C>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    C: PentodeCalc<F, M>,
+    FI: PentodeFilter<F, M>,
+    FO: PentodeFilter<F, M>,
+    FC: PentodeCathodeFilter<F, M>
+{
+    fn i_supply(&self) -> F
+    {
+        self.i_p() + self.i_g2()
+    }
+    fn set_v_pp(&mut self, v_pp: F)
+    {
+        // The quiescent plate voltage follows the supply, so that sag does not step the output
+        if self.calc.track_v_pp(v_pp)
+        {
+            [self.offset, _] = self.calc.vp_a(&self.model, -self.param().v_c);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,84 @@
-use crate::TriodeModel;
+use num::Float;
+
+use crate::{f, TriodeModel, TriodeModelParam, TriodeParam};
 
 pub trait PentodeModel: TriodeModel
 {
     const K_G2: f64;
+}
+
+/// Parameters of a pentode model only known at runtime, e.g. imported from a Koren SPICE subcircuit.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PentodeParam
+{
+    pub triode: TriodeParam,
+    pub k_g2: f64
+}
+
+impl PentodeParam
+{
+    /// Plate current by Koren's equation, given grid-cathode, screen-cathode and plate-cathode voltages.
+    pub fn i_p<F>(&self, v_g: F, v_g2: F, v_p: F) -> F
+    where
+        F: Float
+    {
+        let k_p = f!(self.triode.k_p);
+        let e1 = v_g2/k_p*crate::exp_ln_1p(k_p*(f!(self.triode.mu.recip()) + v_g/v_g2));
+        if e1 > F::zero()
+        {
+            f!(2.0/self.triode.k_g1)*e1.powf(f!(self.triode.ex))*v_p.atan2(f!(self.triode.k_vb))
+        }
+        else
+        {
+            F::zero()
+        }
+    }
+
+    /// Screen current by Koren's equation, given grid-cathode and screen-cathode voltages.
+    pub fn i_g2<F>(&self, v_g: F, v_g2: F) -> F
+    where
+        F: Float
+    {
+        let e2 = v_g + v_g2/f!(self.triode.mu);
+        if e2 > F::zero()
+        {
+            e2.powf(f!(self.triode.ex))/f!(self.k_g2)
+        }
+        else
+        {
+            F::zero()
+        }
+    }
+}
+
+/// A pentode model, either a preset implementing `PentodeModel` or a `PentodeParam`.
+pub trait PentodeModelParam: TriodeModelParam
+{
+    fn pentode(&self) -> PentodeParam;
+}
+impl<M> PentodeModelParam for M
+where
+    M: PentodeModel
+{
+    fn pentode(&self) -> PentodeParam
+    {
+        PentodeParam {
+            triode: self.triode(),
+            k_g2: M::K_G2
+        }
+    }
+}
+impl TriodeModelParam for PentodeParam
+{
+    fn triode(&self) -> TriodeParam
+    {
+        self.triode
+    }
+}
+impl PentodeModelParam for PentodeParam
+{
+    fn pentode(&self) -> PentodeParam
+    {
+        *self
+    }
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use alloc::alloc::Allocator;
 use real_time_fir_iir_filters::param::FilterFloat;
 
 #[cfg(feature = "alloc")]
-use super::{PentodeCache, PentodeModel};
+use super::{PentodeCache, PentodeHermiteCache, PentodeModelParam};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct PentodeClassA<F>
@@ -31,7 +31,7 @@ where
 {
     pub fn cache_in<M, A>(self, range: Range<F>, resolution: usize, alloc: A) -> PentodeCache<F, M, A>
     where
-        M: PentodeModel,
+        M: PentodeModelParam,
         A: Allocator + Clone
     {
         PentodeCache::new_in(self, range, resolution, alloc)
@@ -39,8 +39,23 @@ where
 
     pub fn cache<M>(self, range: Range<F>, resolution: usize) -> PentodeCache<F, M>
     where
-        M: PentodeModel
+        M: PentodeModelParam
     {
         PentodeCache::new(self, range, resolution)
     }
+
+    pub fn hermite_cache_in<M, A>(self, range: Range<F>, resolution: usize, alloc: A) -> PentodeHermiteCache<F, M, A>
+    where
+        M: PentodeModelParam,
+        A: Allocator + Clone
+    {
+        PentodeHermiteCache::new_in(self, range, resolution, alloc)
+    }
+
+    pub fn hermite_cache<M>(self, range: Range<F>, resolution: usize) -> PentodeHermiteCache<F, M>
+    where
+        M: PentodeModelParam
+    {
+        PentodeHermiteCache::new(self, range, resolution)
+    }
 }
\ No newline at end of file
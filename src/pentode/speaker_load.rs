@@ -0,0 +1,267 @@
+use real_time_fir_iir_filters::{param::{FilterFloat, FilterParam}, rtf::Rtf};
+
+use crate::f;
+
+use super::{PentodeClassA, PentodeFilter, PentodeModelParam};
+
+/// A loudspeaker on the secondary of the output transformer, seen from the plate.
+///
+/// The voice coil is `r_e` in series with `l_e`, and the mechanical resonance appears as a parallel RLC peak of height
+/// `r_e*q_ms/q_es` at `f_s`.
+///
+/// Only the small-signal frequency response is modelled. The plate is still solved against the resistive `r_p` of the stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SpeakerLoadParam<F>
+where
+    F: FilterFloat
+{
+    /// Voice coil resistance
+    pub r_e: F,
+    /// Voice coil inductance
+    pub l_e: F,
+    /// Resonance frequency (Hz)
+    pub f_s: F,
+    /// Mechanical Q at resonance
+    pub q_ms: F,
+    /// Electrical Q at resonance
+    pub q_es: F,
+    /// Nominal speaker impedance, which the output transformer maps onto the plate resistor
+    pub z_nom: F,
+    /// Plate resistor, which is the load at the nominal speaker impedance
+    pub r_p: F,
+    /// Internal plate resistance of the tube, damping the resonance
+    pub r_a: F
+}
+
+impl<F> SpeakerLoadParam<F>
+where
+    F: FilterFloat
+{
+    /// A typical 8Ω 12" guitar speaker, driven by a pentode with a plate resistance of 20kΩ.
+    pub fn guitar_12(r_p: F) -> Self
+    {
+        Self {
+            r_e: f!(6.4),
+            l_e: f!(0.7e-3),
+            f_s: f!(85.0),
+            q_ms: f!(6.0),
+            q_es: f!(0.6),
+            z_nom: f!(8.0),
+            r_p,
+            r_a: f!(20e3)
+        }
+    }
+
+    /// A purely resistive load at the nominal impedance, with neither voice coil inductance nor resonance, which passes the plate
+    /// voltage through unchanged.
+    pub fn resistive(r_p: F) -> Self
+    {
+        Self {
+            r_e: f!(8.0),
+            l_e: F::zero(),
+            q_es: F::infinity(),
+            ..Self::guitar_12(r_p)
+        }
+    }
+
+    /// Speaker impedance as a numerator and denominator in ascending powers of `s`.
+    fn impedance(&self) -> ([F; 4], [F; 3])
+    {
+        let Self {r_e, l_e, f_s, q_ms, q_es, ..} = *self;
+
+        let omega = f_s*F::TAU();
+        let r_es = r_e*q_ms/q_es;
+
+        let d = [F::one(), (q_ms*omega).recip(), (omega*omega).recip()];
+        let n = [
+            r_e*d[0],
+            r_e*d[1] + l_e*d[0] + r_es*d[1],
+            r_e*d[2] + l_e*d[1],
+            l_e*d[2]
+        ];
+        (n, d)
+    }
+}
+
+impl<F> FilterParam for SpeakerLoadParam<F>
+where
+    F: FilterFloat
+{
+    const ORDER: usize = 3;
+
+    type F = F;
+}
+
+real_time_fir_iir_filters::def_rtf!(
+    {
+        /// Scales the plate voltage by how the impedance of a speaker differs from its nominal value.
+        ///
+        /// A pentode drives the load close to a current source, so the plate swing follows the speaker impedance, rising at the resonance
+        /// and towards the treble. With the plate resistance `r_a` in parallel, the response is
+        ///
+        /// <pre>
+        ///        Z(s)⋅n² ‖ rₐ
+        /// H(s) = ------------ , n² = rₚ/z_nom
+        ///          rₚ ‖ rₐ
+        /// </pre>
+        ///
+        /// This is a linear equalizer after the plate solve, not a load line. The tube still clips against the resistive `r_p` of the
+        /// stage, so the resonance and the rising treble shape the output, but do not change where or how hard the tube clips.
+        ///
+        /// Only meaningful as the output filter of a [`Pentode`](crate::Pentode). Used as an input filter, it is a resistive load, and the
+        /// grid is left unfiltered.
+    }
+    SpeakerLoad
+    {
+        type Param = SpeakerLoadParam;
+
+        const OUTPUTS: usize = 1;
+        const OUTPUT_BUFS: usize = 1;
+        const SOS_BUFS: usize = 1;
+        const SOS_STAGES: usize = 0;
+        const ORDER: usize = 3;
+        const IS_IIR: bool = true;
+
+        fn make_coeffs(param, rate) -> _
+        {
+            let (n, d) = param.impedance();
+
+            // H = (1 + ρ)Z/(Z + ρz_nom), ρ = rₐ/rₚ
+            let rho = param.r_a/param.r_p;
+            let b = n.map(|n| n*(F::one() + rho));
+            let a = [
+                n[0] + rho*param.z_nom*d[0],
+                n[1] + rho*param.z_nom*d[1],
+                n[2] + rho*param.z_nom*d[2],
+                n[3]
+            ];
+            (
+                ([], [], [
+                    crate::bilinear(rate, b)
+                ]),
+                [([], [
+                    crate::bilinear(rate, a)
+                ])]
+            )
+        }
+    }
+);
+
+impl<F, M> PentodeFilter<F, M> for SpeakerLoad<F>
+where
+    F: FilterFloat,
+    M: PentodeModelParam,
+    Self: Rtf<F = F, Outputs<F> = [F; 1]>
+{
+    fn new_input_filter(_: &M, r_i: F) -> Self
+    {
+        Self::new(SpeakerLoadParam::resistive(r_i))
+    }
+    fn new_output_filter(_: &M, r_p: F) -> Self
+    {
+        Self::new(SpeakerLoadParam::guitar_12(r_p))
+    }
+
+    fn update_miller_effect_input(&mut self, _: &M, _: F)
+    {
+
+    }
+    fn update_miller_effect_output(&mut self, _: &M, _: F)
+    {
+
+    }
+
+    fn vg(&mut self, model: &M, param: PentodeClassA<F>, rate: F, x: F) -> F
+    {
+        PentodeFilter::<F, M>::vg(&mut (), model, param, rate, x)
+    }
+    fn y(&mut self, rate: F, y: F) -> F
+    {
+        let [y] = self.filter(rate, y);
+        y
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::{f32::consts::TAU, ops::Range};
+
+    use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, };
+
+    use crate::{tubes::Tube6L6CG, Pentode};
+
+    use super::*;
+
+    const RATE: f32 = 48000.0;
+
+    fn gain(filter: &mut SpeakerLoad<f32>, freq: f32) -> f32
+    {
+        let [h] = filter.frequency_response(RATE, TAU*freq/RATE);
+        20.0*h.norm().log10()
+    }
+
+    #[test]
+    fn it_works()
+    {
+        // Frequency in decades
+        const RANGE: Range<f32> = 1.0..4.3;
+
+        let mut f0 = SpeakerLoad::new(SpeakerLoadParam::guitar_12(3.4e3));
+        let mut f1 = SpeakerLoad::new(SpeakerLoadParam {
+            r_a: 1.5e3,
+            ..SpeakerLoadParam::guitar_12(3.4e3)
+        });
+        let mut f2 = SpeakerLoad::new(SpeakerLoadParam {
+            l_e: 0.2e-3,
+            f_s: 40.0,
+            ..SpeakerLoadParam::guitar_12(3.4e3)
+        });
+
+        crate::tests::plot(
+            "SpeakerLoad",
+            RANGE,
+            |x| {
+                let freq = 10.0f32.powf(x);
+                [gain(&mut f0, freq), gain(&mut f1, freq), gain(&mut f2, freq)]
+            }
+        )
+    }
+
+    #[test]
+    fn resonance()
+    {
+        const N: usize = 9600;
+
+        let param = PentodeClassA {
+            r_i: 1e3,
+            r_p: 3.4e3,
+            v_pp: 400.0,
+            v_g2: 400.0,
+            v_c: 30.0
+        };
+
+        let peak = |freq: f32| {
+            let mut pentode = Pentode::<_, _, FirstOrderRCFilter<LowPass, _>, SpeakerLoad<_>, (), _>::new(param, Tube6L6CG, ());
+            (0..N).map(|n| pentode.saturate(RATE, (TAU*freq*n as f32/RATE).sin()).abs())
+                .skip(N/2)
+                .fold(0.0, f32::max)
+        };
+
+        // Louder at the resonance than in the midrange, and rising again with the voice coil inductance
+        let [low, mid, high] = [85.0, 400.0, 8e3].map(peak);
+        assert!(low > 2.0*mid);
+        assert!(high > mid);
+    }
+
+    #[test]
+    fn resistive()
+    {
+        // A speaker without voice coil inductance or resonance leaves the plate voltage as it is
+        let mut filter = SpeakerLoad::new(SpeakerLoadParam::resistive(3.4e3));
+        for freq in [20.0, 85.0, 1e3, 10e3]
+        {
+            assert!(gain(&mut filter, freq).abs() < 1e-3);
+        }
+    }
+}
\ No newline at end of file
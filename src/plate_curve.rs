@@ -0,0 +1,105 @@
+use core::{fmt::Write, ops::Range};
+
+use alloc::vec::Vec;
+
+/// Plate current as a function of plate voltage, at a fixed grid voltage.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlateCurve
+{
+    /// Grid-cathode voltage.
+    pub v_g: f64,
+    /// Plate-cathode voltages.
+    pub v_p: Vec<f64>,
+    /// Plate currents in amperes, one for each plate voltage.
+    pub i_p: Vec<f64>
+}
+
+impl PlateCurve
+{
+    pub(crate) fn new(v_g: f64, v_p: Range<f64>, n: usize, i_p: impl Fn(f64) -> f64) -> Self
+    {
+        let step = (v_p.end - v_p.start)/n.saturating_sub(1).max(1) as f64;
+        let v_p: Vec<f64> = (0..n).map(|k| v_p.start + step*k as f64).collect();
+        let i_p = v_p.iter().map(|&v_p| i_p(v_p)).collect();
+        Self { v_g, v_p, i_p }
+    }
+}
+
+/// A plate resistor's load line, and its intersection with the plate curve at a given grid voltage.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LoadLine
+{
+    /// Plate voltage at zero plate current, i.e. the supply voltage.
+    pub v_pp: f64,
+    /// Plate current at zero plate voltage.
+    pub i_max: f64,
+    /// Grid-cathode voltage of the intersection.
+    pub v_g: f64,
+    /// Plate-cathode voltage of the intersection.
+    pub v_p: f64,
+    /// Plate current of the intersection.
+    pub i_p: f64
+}
+
+impl LoadLine
+{
+    /// Solves for the intersection by bisection, given a plate current non-decreasing in plate voltage.
+    pub(crate) fn new(v_pp: f64, r_p: f64, v_g: f64, i_p: impl Fn(f64) -> f64) -> Self
+    {
+        const BISECTION: usize = 64;
+
+        let i_max = v_pp/r_p;
+        let mut v = 0.0..v_pp;
+        for _ in 0..BISECTION
+        {
+            let v_p = (v.start + v.end)*0.5;
+            if i_p(v_p) > (v_pp - v_p)/r_p
+            {
+                v.end = v_p
+            }
+            else
+            {
+                v.start = v_p
+            }
+        }
+        let v_p = (v.start + v.end)*0.5;
+        Self {
+            v_pp,
+            i_max,
+            v_g,
+            v_p,
+            i_p: (v_pp - v_p)/r_p
+        }
+    }
+}
+
+/// Writes plate curves as CSV with the columns `v_g,v_p,i_p`.
+pub fn write_plate_curves_csv<W>(curves: &[PlateCurve], w: &mut W) -> core::fmt::Result
+where
+    W: Write
+{
+    writeln!(w, "v_g,v_p,i_p")?;
+    for curve in curves
+    {
+        for (v_p, i_p) in curve.v_p.iter().zip(&curve.i_p)
+        {
+            writeln!(w, "{},{},{}", curve.v_g, v_p, i_p)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn csv()
+    {
+        let curves = [PlateCurve::new(-1.0, 0.0..100.0, 3, |v_p| v_p*1e-5)];
+        let mut csv = String::new();
+        write_plate_curves_csv(&curves, &mut csv).unwrap();
+        assert_eq!(csv, "v_g,v_p,i_p\n-1,0,0\n-1,50,0.0005\n-1,100,0.001\n");
+    }
+}
\ No newline at end of file
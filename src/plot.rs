@@ -51,7 +51,7 @@ pub fn plot_curves<const N: usize, const M: usize>(
     }
     
     chart.configure_series_labels()
-        .border_style(&BLACK)
+        .border_style(BLACK)
         .draw()?;
         
     // To avoid the IO failure being ignored silently, we manually call the present function
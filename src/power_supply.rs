@@ -0,0 +1,313 @@
+use num::Float;
+
+use crate::f;
+
+/// Rectifier charging the reservoir capacitor of a power supply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Rectifier
+{
+    /// Silicon diodes, with a small forward drop and practically no resistance.
+    #[default]
+    SolidState,
+    /// A vacuum diode, roughly a GZ34, whose internal resistance adds to the sag.
+    Tube
+}
+
+impl Rectifier
+{
+    /// Forward voltage drop (Volt)
+    fn v_f(self) -> f64
+    {
+        match self
+        {
+            Self::SolidState => 1.4,
+            Self::Tube => 15.0
+        }
+    }
+
+    /// Internal resistance while conducting (Ohm)
+    fn r_d(self) -> f64
+    {
+        match self
+        {
+            Self::SolidState => 1.0,
+            Self::Tube => 150.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PowerSupplyParam<F>
+where
+    F: Float
+{
+    /// Peak voltage of the transformer secondary
+    pub v_s: F,
+    /// Internal resistance of the transformer
+    pub r_s: F,
+    /// Reservoir capacitor
+    pub c: F,
+    pub rectifier: Rectifier,
+    /// Mains frequency, or `None` for an ideal DC source without ripple
+    pub f_mains: Option<F>
+}
+
+/// A stage drawing current from a `PowerSupply`.
+pub trait PowerSupplyLoad<F>
+{
+    /// Current drawn from the supply during the last sample, including the screen current of a pentode.
+    fn i_supply(&self) -> F;
+    /// Moves the plate supply, and the screen supply of a pentode along with it.
+    fn set_v_pp(&mut self, v_pp: F);
+}
+
+/// A full-wave rectified power supply with a reservoir capacitor, sagging under load and rippling at twice the mains frequency.
+///
+/// Each sample, the capacitor is charged through the rectifier whenever the rectified mains exceeds it, and discharged by the load.
+///
+/// A stage with a cached calc only rebuilds its table once the supply has moved by 2% of the voltage the table was built for, and
+/// runs at that voltage until then. The ripple of a small reservoir capacitor can exceed that band, rebuilding the table twice per
+/// mains cycle, in which case an uncached calc like `TriodeClassA` or `PentodeClassA` is cheaper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PowerSupply<F>
+where
+    F: Float
+{
+    param: PowerSupplyParam<F>,
+    v: F,
+    phase: F
+}
+
+impl<F> PowerSupply<F>
+where
+    F: Float
+{
+    pub fn new(param: PowerSupplyParam<F>) -> Self
+    {
+        let mut supply = Self {
+            param,
+            v: F::zero(),
+            phase: F::zero()
+        };
+        supply.reset();
+        supply
+    }
+
+    pub fn param(&self) -> &PowerSupplyParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut PowerSupplyParam<F>
+    {
+        &mut self.param
+    }
+
+    /// Current supply voltage.
+    pub fn v_pp(&self) -> F
+    {
+        self.v
+    }
+
+    /// Charges the capacitor to the unloaded supply voltage.
+    pub fn reset(&mut self)
+    {
+        self.v = (self.param.v_s - f!(self.param.rectifier.v_f())).max(F::zero());
+        self.phase = F::zero();
+    }
+
+    /// Advances the supply by one sample with a total load current `i_load`, returning the new supply voltage.
+    pub fn saturate(&mut self, rate: F, i_load: F) -> F
+    {
+        let PowerSupplyParam {v_s, r_s, c, rectifier, f_mains} = self.param;
+
+        let one = F::one();
+        let dt = rate.recip();
+
+        let v_src = match f_mains
+        {
+            Some(f_mains) => {
+                self.phase = self.phase + f_mains*dt;
+                self.phase = self.phase - self.phase.floor();
+                v_s*(f!(core::f64::consts::TAU)*self.phase).sin().abs()
+            },
+            None => v_s
+        } - f!(rectifier.v_f());
+        let g = (r_s + f!(rectifier.r_d())).recip();
+
+        // Backward Euler, charging only while the rectifier is forward biased
+        let v = self.v - dt/c*i_load;
+        let v_charged = (self.v + dt/c*(g*v_src - i_load))/(one + dt/c*g);
+        self.v = if v_charged < v_src
+        {
+            v_charged
+        }
+        else
+        {
+            v
+        }.max(F::zero());
+
+        self.v
+    }
+
+    /// Advances the supply by one sample with the current drawn by `loads`, and feeds the new supply voltage back into them.
+    pub fn supply(&mut self, rate: F, loads: &mut [&mut dyn PowerSupplyLoad<F>]) -> F
+    {
+        let i_load = loads.iter()
+            .map(|load| load.i_supply())
+            .fold(F::zero(), |a, b| a + b);
+        let v_pp = self.saturate(rate, i_load);
+        for load in loads.iter_mut()
+        {
+            load.set_v_pp(v_pp)
+        }
+        v_pp
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::{tubes::{Tube12AX7, Tube6L6CG}, Pentode, PentodeClassA, Triode, TriodeClassA};
+
+    use super::*;
+
+    fn param(rectifier: Rectifier, f_mains: Option<f32>) -> PowerSupplyParam<f32>
+    {
+        PowerSupplyParam {
+            v_s: 350.0,
+            r_s: 50.0,
+            c: 47e-6,
+            rectifier,
+            f_mains
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = 0.0..0.2;
+        // One sample per plotted point
+        const RATE: f32 = 512.0/(RANGE.end - RANGE.start);
+        const I_LOAD: f32 = 0.1;
+
+        let mut s0 = PowerSupply::new(param(Rectifier::SolidState, None));
+        let mut s1 = PowerSupply::new(param(Rectifier::SolidState, Some(50.0)));
+        let mut s2 = PowerSupply::new(param(Rectifier::Tube, Some(50.0)));
+
+        // Full load is switched on at half time
+        crate::tests::plot(
+            "PowerSupply",
+            RANGE,
+            |t| {
+                let i = if t >= (RANGE.start + RANGE.end)/2.0 {I_LOAD} else {0.0};
+                [s0.saturate(RATE, i), s1.saturate(RATE, i), s2.saturate(RATE, i)]
+            }
+        )
+    }
+
+    #[test]
+    fn sag()
+    {
+        const RATE: f32 = 48000.0;
+        const N: usize = 4800;
+
+        let mut solid_state = PowerSupply::new(param(Rectifier::SolidState, Some(60.0)));
+        let mut tube = PowerSupply::new(param(Rectifier::Tube, Some(60.0)));
+
+        let mut triode = Triode::<_, Tube12AX7, (), (), (), _>::new(
+            TriodeClassA {
+                r_i: 1e3,
+                r_p: 1e3,
+                v_pp: 350.0,
+                v_c: 0.0
+            },
+            Tube12AX7,
+            ()
+        );
+
+        let mut v = [0.0; 2];
+        for _ in 0..N
+        {
+            triode.saturate(RATE, 2.0);
+            v[0] = solid_state.supply(RATE, &mut [&mut triode]);
+            v[1] = tube.saturate(RATE, triode.i_p());
+        }
+
+        assert!(v[0] < 350.0 && v[1] < v[0]);
+        assert_eq!(triode.param().v_pp, v[0]);
+
+        // The output is relative to the quiescent point of the sagged supply
+        let sagged = Triode::<_, Tube12AX7, (), (), (), _>::new(*triode.param(), Tube12AX7, ());
+        assert_eq!(triode.offset(), sagged.offset());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn cached()
+    {
+        use real_time_fir_iir_filters::param::RC;
+
+        const RATE: f32 = 48000.0;
+        const N: usize = 4800;
+
+        let mut supply = PowerSupply::new(param(Rectifier::Tube, None));
+
+        // A stage of the default type, with a cached calc
+        let calc = TriodeClassA {
+            r_i: 1e3,
+            r_p: 1e3,
+            v_pp: 350.0,
+            v_c: 0.0
+        };
+        let mut triode = Triode::<_, Tube12AX7>::new(calc.cache(-20.0..20.0, 256), Tube12AX7, RC {r: 0.0, c: 0.0});
+
+        let mut v = 0.0;
+        for _ in 0..N
+        {
+            triode.saturate(RATE, 2.0);
+            v = supply.supply(RATE, &mut [&mut triode]);
+        }
+
+        // The table was rebuilt as the supply sagged, and lags behind it by no more than the hysteresis
+        let v_pp = triode.param().v_pp;
+        assert!(v_pp < 350.0);
+        assert!((v_pp - v).abs() <= 0.02*v_pp);
+
+        let sagged = Triode::<_, Tube12AX7>::new(triode.param().cache(-20.0..20.0, 256), Tube12AX7, RC {r: 0.0, c: 0.0});
+        assert_eq!(triode.offset(), sagged.offset());
+    }
+
+    #[test]
+    fn screen()
+    {
+        const RATE: f32 = 48000.0;
+        const N: usize = 4800;
+
+        let mut supply = PowerSupply::new(param(Rectifier::SolidState, None));
+        let mut pentode = Pentode::<_, Tube6L6CG, (), (), (), _>::new(
+            PentodeClassA {
+                r_i: 1e3,
+                r_p: 3.4e3,
+                v_pp: 350.0,
+                v_g2: 340.0,
+                v_c: 10.0
+            },
+            Tube6L6CG,
+            ()
+        );
+
+        // The supply also feeds the screen
+        assert!(pentode.i_supply() > pentode.i_p());
+        for _ in 0..N
+        {
+            pentode.saturate(RATE, 10.0);
+            supply.supply(RATE, &mut [&mut pentode]);
+        }
+
+        // ...whose voltage sags along with the plate supply
+        assert!(pentode.param().v_pp < 350.0);
+        assert!((pentode.param().v_pp - pentode.param().v_g2 - 10.0).abs() < 1e-2);
+    }
+}
\ No newline at end of file
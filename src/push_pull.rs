@@ -0,0 +1,263 @@
+use num::Float;
+
+use crate::{f, tubes::Tube6L6CG, PentodeModelParam, PentodeParam};
+
+/// Bias setting of a push-pull stage, as a fraction of the grid voltage at which the tubes cut off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum PushPullClass
+{
+    /// Biased to half of cutoff. Both tubes conduct over the whole cycle.
+    A,
+    /// Biased to three quarters of cutoff. Each tube cuts off for part of the cycle.
+    #[default]
+    AB,
+    /// Biased to cutoff. Each tube conducts for half of the cycle, with crossover distortion where they hand over.
+    B
+}
+
+impl PushPullClass
+{
+    fn cutoff_fraction(self) -> f64
+    {
+        match self
+        {
+            Self::A => 0.5,
+            Self::AB => 0.75,
+            Self::B => 1.0
+        }
+    }
+}
+
+/// How the screen grids of the tubes are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum PushPullConnection
+{
+    /// Screens at a fixed supply voltage.
+    #[default]
+    Pentode,
+    /// Screens tied to the plates.
+    Triode
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PushPullParam<F>
+where
+    F: Float
+{
+    /// Supply voltage at the center tap of the output transformer
+    pub v_pp: F,
+    /// Screen gate voltage, if pentode connected
+    pub v_g2: F,
+    /// Plate-to-plate load impedance of the output transformer
+    pub r_aa: F,
+    /// Turns ratio of the output transformer, primary to secondary
+    pub n: F,
+    pub class: PushPullClass,
+    pub connection: PushPullConnection
+}
+
+/// A push-pull power amplifier stage, with two tubes driving a center-tapped output transformer.
+///
+/// The transformer is ideal, so only the difference of the plate currents reaches the output, cancelling even harmonics when the tubes are
+/// matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PushPull<F, M = Tube6L6CG>
+where
+    F: Float,
+    M: PentodeModelParam
+{
+    param: PushPullParam<F>,
+    v_bias: F,
+    offset: F,
+    model_a: M,
+    model_b: M
+}
+
+impl<F, M> PushPull<F, M>
+where
+    F: Float,
+    M: PentodeModelParam
+{
+    pub fn new(param: PushPullParam<F>) -> Self
+    where
+        M: Default
+    {
+        Self::with_models(param, M::default(), M::default())
+    }
+
+    pub fn with_models(param: PushPullParam<F>, model_a: M, model_b: M) -> Self
+    {
+        let mut push_pull = Self {
+            param,
+            v_bias: F::zero(),
+            offset: F::zero(),
+            model_a,
+            model_b
+        };
+        push_pull.calibrate();
+        push_pull
+    }
+
+    pub fn param(&self) -> &PushPullParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut PushPullParam<F>
+    {
+        &mut self.param
+    }
+    pub fn models(&self) -> [&M; 2]
+    {
+        [&self.model_a, &self.model_b]
+    }
+    pub fn models_mut(&mut self) -> [&mut M; 2]
+    {
+        [&mut self.model_a, &mut self.model_b]
+    }
+
+    /// Grid bias voltage, set from the class and the first tube's cutoff.
+    pub fn v_bias(&self) -> F
+    {
+        self.v_bias
+    }
+
+    /// Recomputes the bias and quiescent output. Must be called after changing the parameters or models.
+    pub fn calibrate(&mut self)
+    {
+        let v_g2 = match self.param.connection
+        {
+            PushPullConnection::Pentode => self.param.v_g2,
+            PushPullConnection::Triode => self.param.v_pp
+        };
+        let mu = f!(self.model_a.triode().mu);
+        self.v_bias = -v_g2/mu*f!(self.param.class.cutoff_fraction());
+        self.offset = F::zero();
+        self.offset = self.v_primary(F::zero(), F::zero());
+    }
+
+    fn i_p(&self, model: PentodeParam, v_g: F, v_p: F) -> F
+    {
+        let v_p = v_p.max(F::zero());
+        let v_g2 = match self.param.connection
+        {
+            PushPullConnection::Pentode => self.param.v_g2,
+            PushPullConnection::Triode => v_p
+        };
+        model.i_p(v_g, v_g2, v_p)
+    }
+
+    /// Voltage across the transformer primary, relative to the quiescent voltage.
+    fn v_primary(&self, x_a: F, x_b: F) -> F
+    {
+        let one = F::one();
+        let two = one + one;
+
+        let model_a = self.model_a.pentode();
+        let model_b = self.model_b.pentode();
+        let v_g_a = self.v_bias + x_a;
+        let v_g_b = self.v_bias + x_b;
+        let r = self.param.r_aa/two;
+
+        // The differential plate current develops the primary voltage, which in turn swings the plates in opposite directions
+        let f = |v: F| v - r*(self.i_p(model_a, v_g_a, self.param.v_pp - v/two) - self.i_p(model_b, v_g_b, self.param.v_pp + v/two));
+
+        // The plates stay between ground and twice the supply, so the root is bracketed
+        let bracket = [-two*self.param.v_pp, two*self.param.v_pp];
+        let v = r*(self.i_p(model_a, v_g_a, self.param.v_pp) - self.i_p(model_b, v_g_b, self.param.v_pp));
+
+        const NEWTON: usize = 12;
+        const DELTA: f64 = 1e-2;
+
+        let v = crate::newton_bracketed(f, v, bracket, self.param.v_pp*f!(DELTA), NEWTON);
+
+        v - self.offset
+    }
+
+    /// Output given separate drive voltages for each grid, e.g. from a phase inverter.
+    pub fn saturate_pair(&self, x_a: F, x_b: F) -> F
+    {
+        self.v_primary(x_a, x_b)/self.param.n
+    }
+
+    /// Output given a symmetric drive, with the grids driven by `x` and `-x`.
+    pub fn saturate(&self, x: F) -> F
+    {
+        self.saturate_pair(x, -x)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::tubes::{Tube6550, TubeKT88};
+
+    use super::*;
+
+    fn param(class: PushPullClass, connection: PushPullConnection) -> PushPullParam<f32>
+    {
+        PushPullParam {
+            v_pp: 450.0,
+            v_g2: 400.0,
+            r_aa: 4.3e3,
+            n: 23.2,
+            class,
+            connection
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -60.0..60.0;
+
+        let p0 = PushPull::<_, Tube6L6CG>::new(param(PushPullClass::A, PushPullConnection::Pentode));
+        let p1 = PushPull::<_, Tube6L6CG>::new(param(PushPullClass::AB, PushPullConnection::Pentode));
+        let p2 = PushPull::<_, Tube6L6CG>::new(param(PushPullClass::B, PushPullConnection::Pentode));
+        let p3 = PushPull::<_, Tube6L6CG>::new(param(PushPullClass::AB, PushPullConnection::Triode));
+        let p4 = PushPull::<_, Tube6550>::new(param(PushPullClass::AB, PushPullConnection::Pentode));
+        let p5 = PushPull::<_, TubeKT88>::new(param(PushPullClass::AB, PushPullConnection::Pentode));
+
+        crate::tests::plot(
+            "PushPull",
+            RANGE,
+            |x| [
+                p0.saturate(x),
+                p1.saturate(x),
+                p2.saturate(x),
+                p3.saturate(x),
+                p4.saturate(x),
+                p5.saturate(x)
+            ]
+        )
+    }
+
+    #[test]
+    fn crossover()
+    {
+        let gain = |class| {
+            let push_pull = PushPull::<_, Tube6L6CG>::new(param(class, PushPullConnection::Pentode));
+            push_pull.saturate(0.1)/0.1
+        };
+
+        // Near cutoff, neither tube has much transconductance
+        assert!(gain(PushPullClass::B) < 0.5*gain(PushPullClass::A));
+    }
+
+    #[test]
+    fn mismatch()
+    {
+        let matched = PushPull::<_, _>::with_models(param(PushPullClass::AB, PushPullConnection::Pentode), Tube6L6CG.pentode(), Tube6L6CG.pentode());
+        let mut model_b = Tube6L6CG.pentode();
+        model_b.triode.k_g1 *= 1.2;
+        let mismatched = PushPull::<_, _>::with_models(param(PushPullClass::AB, PushPullConnection::Pentode), Tube6L6CG.pentode(), model_b);
+
+        // Even harmonics cancel only for matched tubes
+        for x in [5.0, 15.0, 30.0]
+        {
+            assert!((matched.saturate(x) + matched.saturate(-x)).abs() < 1e-3);
+            assert!((mismatched.saturate(x) + mismatched.saturate(-x)).abs() > 1e-2);
+        }
+    }
+}
\ No newline at end of file
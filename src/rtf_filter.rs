@@ -0,0 +1,145 @@
+/// Adapts any filter with a single output into an input or output filter of a [`Triode`](crate::Triode) or [`Pentode`](crate::Pentode).
+///
+/// The filter sees the input signal before the grid bias is applied, so it may block DC, like a coupling network of a grid leak
+/// resistor and a capacitor as a `FirstOrderRCFilter<HighPass, F>`. It does not follow the Miller capacitance.
+///
+/// Since the filter can't be made from the tube's parameters, the signal passes through untouched until a filter is set, e.g. through
+/// [`Triode::input_filter_mut`](crate::Triode::input_filter_mut).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RtfFilter<T>
+{
+    filter: Option<T>
+}
+
+impl<T> RtfFilter<T>
+{
+    pub fn new(filter: T) -> Self
+    {
+        Self {
+            filter: Some(filter)
+        }
+    }
+
+    pub fn filter(&self) -> Option<&T>
+    {
+        self.filter.as_ref()
+    }
+    pub fn filter_mut(&mut self) -> Option<&mut T>
+    {
+        self.filter.as_mut()
+    }
+    pub fn set_filter(&mut self, filter: Option<T>)
+    {
+        self.filter = filter
+    }
+}
+
+impl<T> Default for RtfFilter<T>
+{
+    fn default() -> Self
+    {
+        Self {
+            filter: None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use real_time_fir_iir_filters::{conf::{HighPass, LowPass}, filters::iir::{first::FirstOrderRCFilter, second::SecondOrderRLCFilter}, param::RC};
+
+    use crate::{tubes::Tube12AX7, Triode, TriodeClassA};
+
+    use super::*;
+
+    /// Grid leak resistor and coupling capacitor
+    const COUPLING: RC<f32> = RC {
+        r: 1e6,
+        c: 22e-9
+    };
+
+    fn param() -> TriodeClassA<f32>
+    {
+        TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 1.5
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = 0.0..0.1;
+        // One sample per plotted point
+        const RATE: f32 = 512.0/(RANGE.end - RANGE.start);
+        const X: f32 = 0.5;
+
+        let mut t0 = Triode::<_, Tube12AX7, FirstOrderRCFilter<LowPass, f32>, (), (), _>::new(param(), Tube12AX7, ());
+        let mut t1 = Triode::<_, Tube12AX7, RtfFilter<FirstOrderRCFilter<HighPass, f32>>, (), (), _>::new(param(), Tube12AX7, ());
+        *t1.input_filter_mut() = RtfFilter::new(FirstOrderRCFilter::new(COUPLING));
+        let mut t2 = Triode::<_, Tube12AX7, SecondOrderRLCFilter<LowPass, f32>, (), (), _>::new(param(), Tube12AX7, ());
+        t2.input_filter_mut().param.l = 2e-3;
+        let mut t3 = Triode::<_, Tube12AX7, RtfFilter<FirstOrderRCFilter<HighPass, f32>>, (), (), _>::new(param(), Tube12AX7, ());
+        *t3.input_filter_mut() = RtfFilter::new(FirstOrderRCFilter::new(RC {
+            r: 100e3,
+            c: 22e-9
+        }));
+
+        // Step response
+        crate::tests::plot(
+            "RtfFilter",
+            RANGE,
+            |t| {
+                let x = if t >= 0.01 {X} else {0.0};
+                [
+                    t0.saturate(RATE, x),
+                    t1.saturate(RATE, x),
+                    t2.saturate(RATE, x),
+                    t3.saturate(RATE, x)
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn coupling()
+    {
+        const RATE: f32 = 48000.0;
+        const N: usize = 48000;
+
+        let mut direct = Triode::<_, Tube12AX7, FirstOrderRCFilter<LowPass, f32>, (), (), _>::new(param(), Tube12AX7, ());
+        let mut coupled = Triode::<_, Tube12AX7, RtfFilter<FirstOrderRCFilter<HighPass, f32>>, (), (), _>::new(param(), Tube12AX7, ());
+        *coupled.input_filter_mut() = RtfFilter::new(FirstOrderRCFilter::new(COUPLING));
+
+        let mut y = [0.0; 2];
+        for _ in 0..N
+        {
+            y = [direct.saturate(RATE, 0.5), coupled.saturate(RATE, 0.5)];
+        }
+
+        // The coupling capacitor has charged up, so only the direct triode still sees the offset
+        assert!(y[0].abs() > 1.0);
+        assert!(y[1].abs() < 1e-2);
+    }
+
+    #[test]
+    fn rlc()
+    {
+        const RATE: f32 = 48000.0;
+        const N: usize = 480;
+
+        // Without inductance, the RLC network is the RC low-pass
+        let mut rc = Triode::<_, Tube12AX7, FirstOrderRCFilter<LowPass, f32>, (), (), _>::new(param(), Tube12AX7, ());
+        let mut rlc = Triode::<_, Tube12AX7, SecondOrderRLCFilter<LowPass, f32>, (), (), _>::new(param(), Tube12AX7, ());
+        for n in 0..N
+        {
+            let x = (n as f32*0.1).sin();
+            assert!((rc.saturate(RATE, x) - rlc.saturate(RATE, x)).abs() < 1e-3);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,625 @@
+use core::fmt::Display;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec
+};
+
+#[cfg(feature = "diodes")]
+use crate::{thermal, DiodeParam};
+#[cfg(feature = "jfets")]
+use crate::JFETParam;
+#[cfg(feature = "tubes")]
+use crate::{PentodeParam, TriodeParam};
+
+/// Diode parameters that are valid in a SPICE model card, but have no effect on the model.
+///
+/// These only describe the junction capacitance, noise and ratings. Parameters changing the DC curve, like breakdown, recombination or
+/// high-injection, are rejected instead.
+#[cfg(feature = "diodes")]
+const DIODE_IGNORED: &[&str] = &["CJO", "CJ0", "CJ", "VJ", "PB", "M", "MJ", "FC", "TT", "KF", "AF", "MFG", "TYPE", "VPK", "IPK", "IAVE"];
+/// JFET parameters that are valid in a SPICE model card, but have no effect on the model.
+///
+/// These only describe the junction capacitance, gate leakage and noise. Parameters changing the drain current, like the terminal
+/// resistances, impact ionization, doping tail or temperature coefficients, are rejected instead.
+#[cfg(feature = "jfets")]
+const JFET_IGNORED: &[&str] = &["CGS", "CGD", "PB", "FC", "IS", "N", "ISR", "NR", "XTI", "M", "KF", "AF", "MFG", "TYPE"];
+
+/// A device model read from a SPICE netlist.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpiceModel
+{
+    pub name: String,
+    pub device: SpiceDevice
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SpiceDevice
+{
+    #[cfg(feature = "diodes")]
+    Diode(DiodeParam),
+    #[cfg(feature = "jfets")]
+    JFET(JFETParam),
+    #[cfg(feature = "tubes")]
+    Triode(TriodeParam),
+    #[cfg(feature = "tubes")]
+    Pentode(PentodeParam)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpiceError
+{
+    /// The statement starting at `line` is malformed.
+    Syntax
+    {
+        line: usize, message: &'static str
+    },
+    /// The model is of a kind of device this crate does not simulate.
+    UnsupportedDevice
+    {
+        model: String, device: String
+    },
+    /// The model has a parameter this crate does not know of.
+    UnsupportedParameter
+    {
+        model: String, parameter: String
+    },
+    /// The model lacks a parameter that has no sensible default.
+    MissingParameter
+    {
+        model: String, parameter: &'static str
+    },
+    /// A parameter value is not a number.
+    InvalidValue
+    {
+        model: String, parameter: String, value: String
+    },
+    /// The netlist contains no model of the requested kind.
+    NoModel
+}
+
+impl Display for SpiceError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self
+        {
+            Self::Syntax { line, message } => write!(f, "syntax error on line {line}: {message}"),
+            Self::UnsupportedDevice { model, device } => write!(f, "model {model} is of unsupported device type {device}"),
+            Self::UnsupportedParameter { model, parameter } => write!(f, "model {model} has unsupported parameter {parameter}"),
+            Self::MissingParameter { model, parameter } => write!(f, "model {model} is missing parameter {parameter}"),
+            Self::InvalidValue { model, parameter, value } => write!(f, "model {model} has invalid value {value} for parameter {parameter}"),
+            Self::NoModel => write!(f, "no model of the requested kind found")
+        }
+    }
+}
+impl core::error::Error for SpiceError {}
+
+type Param = (String, String);
+
+/// Parses every `.model` card and Koren-style `.subckt` tube model in a SPICE netlist.
+///
+/// Diodes (`D`) and n-channel JFETs (`NJF`) are read from `.model` cards. Subcircuits are read as triodes from their `PARAMS:` (or `.param`
+/// statements), and as pentodes if `KG2` is given. Other subcircuit contents are ignored.
+pub fn parse(netlist: &str) -> Result<Vec<SpiceModel>, SpiceError>
+{
+    let mut models = Vec::new();
+    let mut subckt: Option<(usize, String, Vec<Param>)> = None;
+
+    for (line, statement) in statements(netlist)
+    {
+        let tokens = tokenize(&statement);
+        let Some(keyword) = tokens.first()
+        else
+        {
+            continue
+        };
+        match keyword.to_ascii_lowercase().as_str()
+        {
+            ".model" => {
+                let (Some(name), Some(device)) = (tokens.get(1), tokens.get(2))
+                else
+                {
+                    return Err(SpiceError::Syntax {
+                        line,
+                        message: "expected model name and device type"
+                    })
+                };
+                let params = params(line, &tokens[3..])?;
+                models.push(SpiceModel {
+                    name: name.clone(),
+                    device: model_device(name, device, &params)?
+                });
+            },
+            ".subckt" => {
+                let Some(name) = tokens.get(1)
+                else
+                {
+                    return Err(SpiceError::Syntax {
+                        line,
+                        message: "expected subcircuit name"
+                    })
+                };
+                if subckt.is_some()
+                {
+                    return Err(SpiceError::Syntax {
+                        line,
+                        message: "nested subcircuit"
+                    })
+                }
+                let params = match tokens.iter().position(|token| token.eq_ignore_ascii_case("params:"))
+                {
+                    Some(i) => params(line, &tokens[i + 1..])?,
+                    None => Vec::new()
+                };
+                subckt = Some((line, name.clone(), params))
+            },
+            ".param" => {
+                if let Some((_, _, params_subckt)) = &mut subckt
+                {
+                    params_subckt.extend(params(line, &tokens[1..])?)
+                }
+            },
+            ".ends" => {
+                let Some((_, name, params)) = subckt.take()
+                else
+                {
+                    return Err(SpiceError::Syntax {
+                        line,
+                        message: "unexpected .ends"
+                    })
+                };
+                models.push(SpiceModel {
+                    device: subckt_device(&name, &params)?,
+                    name
+                });
+            },
+            _ => ()
+        }
+    }
+
+    if let Some((line, _, _)) = subckt
+    {
+        return Err(SpiceError::Syntax {
+            line,
+            message: "subcircuit without .ends"
+        })
+    }
+
+    Ok(models)
+}
+
+/// Joins continuation lines and strips comments, yielding each statement with the line it starts on.
+fn statements(netlist: &str) -> Vec<(usize, String)>
+{
+    let mut statements: Vec<(usize, String)> = Vec::new();
+    for (i, line) in netlist.lines().enumerate()
+    {
+        let line = line.split([';', '$']).next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('*')
+        {
+            continue
+        }
+        match (line.strip_prefix('+'), statements.last_mut())
+        {
+            (Some(continuation), Some((_, statement))) => {
+                statement.push(' ');
+                statement.push_str(continuation)
+            },
+            _ => statements.push((i + 1, line.to_string()))
+        }
+    }
+    statements
+}
+
+fn tokenize(statement: &str) -> Vec<String>
+{
+    let mut normalized = String::with_capacity(statement.len());
+    for c in statement.chars()
+    {
+        match c
+        {
+            '(' | ')' | ',' => normalized.push(' '),
+            '=' => {
+                while normalized.ends_with(' ')
+                {
+                    normalized.pop();
+                }
+                normalized.push('=')
+            },
+            c if c.is_whitespace() && normalized.ends_with('=') => (),
+            c => normalized.push(c)
+        }
+    }
+    normalized.split_whitespace().map(ToString::to_string).collect()
+}
+
+fn params(line: usize, tokens: &[String]) -> Result<Vec<Param>, SpiceError>
+{
+    tokens
+        .iter()
+        .map(|token| {
+            token
+                .split_once('=')
+                .map(|(name, value)| (name.to_ascii_uppercase(), value.to_string()))
+                .ok_or(SpiceError::Syntax {
+                    line,
+                    message: "expected parameter on the form NAME=VALUE"
+                })
+        })
+        .collect()
+}
+
+/// Parses a number with an optional SPICE scale factor, ignoring any trailing unit.
+fn number(value: &str) -> Option<f64>
+{
+    let value = value.trim_matches(['{', '}']).to_ascii_lowercase();
+    let bytes = value.as_bytes();
+
+    let mut end = 0;
+    let digits = |end: &mut usize| {
+        let start = *end;
+        while bytes.get(*end).is_some_and(u8::is_ascii_digit)
+        {
+            *end += 1
+        }
+        *end > start
+    };
+    if matches!(bytes.first(), Some(b'+' | b'-'))
+    {
+        end += 1
+    }
+    let mut mantissa = digits(&mut end);
+    if bytes.get(end) == Some(&b'.')
+    {
+        end += 1;
+        mantissa |= digits(&mut end);
+    }
+    if !mantissa
+    {
+        return None
+    }
+    let mantissa = end;
+    let mut exponent = 0;
+    if bytes.get(end) == Some(&b'e')
+    {
+        let mut exponent_end = end + 1;
+        if matches!(bytes.get(exponent_end), Some(b'+' | b'-'))
+        {
+            exponent_end += 1
+        }
+        if digits(&mut exponent_end)
+        {
+            exponent = value[end + 1..exponent_end].parse::<i32>().ok()?;
+            end = exponent_end
+        }
+    }
+
+    // Applying the scale factor to the exponent before parsing keeps e.g. `25n` exactly equal to `25e-9`
+    let suffix = &value[end..];
+    let (scale, mil) = if suffix.starts_with("meg")
+    {
+        (6, false)
+    }
+    else if suffix.starts_with("mil")
+    {
+        (-6, true)
+    }
+    else
+    {
+        let scale = match suffix.chars().next()
+        {
+            Some('t') => 12,
+            Some('g') => 9,
+            Some('k') => 3,
+            Some('m') => -3,
+            Some('u' | 'µ') => -6,
+            Some('n') => -9,
+            Some('p') => -12,
+            Some('f') => -15,
+            Some(c) if c.is_ascii_alphabetic() => 0,
+            None => 0,
+            Some(_) => return None
+        };
+        (scale, false)
+    };
+    let x: f64 = format!("{}e{}", &value[..mantissa], exponent + scale).parse().ok()?;
+    Some(if mil { x*25.4 } else { x })
+}
+
+/// Looks up parameters by name, checking that no other parameters are given.
+struct Params<'a>
+{
+    model: &'a str,
+    params: &'a [Param]
+}
+
+#[allow(unused)]
+impl<'a> Params<'a>
+{
+    fn new(model: &'a str, params: &'a [Param], supported: &[&str], ignored: &[&str]) -> Result<Self, SpiceError>
+    {
+        if let Some((parameter, _)) = params
+            .iter()
+            .find(|(name, _)| !supported.contains(&name.as_str()) && !ignored.contains(&name.as_str()))
+        {
+            return Err(SpiceError::UnsupportedParameter {
+                model: model.to_string(),
+                parameter: parameter.clone()
+            })
+        }
+        Ok(Self { model, params })
+    }
+
+    fn get(&self, names: &[&str]) -> Result<Option<f64>, SpiceError>
+    {
+        match self.params.iter().rev().find(|(name, _)| names.contains(&name.as_str()))
+        {
+            Some((parameter, value)) => number(value).map(Some).ok_or_else(|| SpiceError::InvalidValue {
+                model: self.model.to_string(),
+                parameter: parameter.clone(),
+                value: value.clone()
+            }),
+            None => Ok(None)
+        }
+    }
+
+    fn get_or(&self, names: &[&str], default: f64) -> Result<f64, SpiceError>
+    {
+        self.get(names).map(|x| x.unwrap_or(default))
+    }
+
+    fn require(&self, name: &'static str) -> Result<f64, SpiceError>
+    {
+        self.get(&[name])?.ok_or_else(|| SpiceError::MissingParameter {
+            model: self.model.to_string(),
+            parameter: name
+        })
+    }
+}
+
+#[allow(unused)]
+fn model_device(name: &str, device: &str, params: &[Param]) -> Result<SpiceDevice, SpiceError>
+{
+    match device.to_ascii_uppercase().as_str()
+    {
+        #[cfg(feature = "diodes")]
+        "D" => {
+            let params = Params::new(name, params, &["IS", "N", "RS", "EG", "XTI", "TNOM", "AREA"], DIODE_IGNORED)?;
+            let area = params.get_or(&["AREA"], 1.0)?;
+            Ok(SpiceDevice::Diode(DiodeParam {
+                i_0: params.get_or(&["IS"], 1e-14)?*area,
+                eta: params.get_or(&["N"], 1.0)?,
+                r_s: params.get_or(&["RS"], 0.0)?/area,
+                e_g: params.get_or(&["EG"], 1.11)?,
+                xti: params.get_or(&["XTI"], 3.0)?,
+                t_nom: params.get_or(&["TNOM"], 27.0)? + thermal::ZERO_CELSIUS
+            }))
+        },
+        #[cfg(feature = "jfets")]
+        "NJF" => {
+            let params = Params::new(name, params, &["BETA", "VTO", "LAMBDA", "AREA"], JFET_IGNORED)?;
+            Ok(SpiceDevice::JFET(JFETParam {
+                beta: params.get_or(&["BETA"], 1e-4)?*params.get_or(&["AREA"], 1.0)?,
+                vto: params.get_or(&["VTO"], -2.0)?,
+                lambda: params.get_or(&["LAMBDA"], 0.0)?
+            }))
+        },
+        _ => Err(SpiceError::UnsupportedDevice {
+            model: name.to_string(),
+            device: device.to_string()
+        })
+    }
+}
+
+#[allow(unused)]
+fn subckt_device(name: &str, params: &[Param]) -> Result<SpiceDevice, SpiceError>
+{
+    #[cfg(feature = "tubes")]
+    {
+        let params = Params::new(
+            name,
+            params,
+            &["MU", "EX", "KG1", "KP", "KVB", "KG2", "RGI", "CCG", "CGP", "CPG", "CPG1", "CCP", "PMAX"],
+            &[]
+        )?;
+        let triode = TriodeParam {
+            mu: params.require("MU")?,
+            ex: params.require("EX")?,
+            k_g1: params.require("KG1")?,
+            k_p: params.require("KP")?,
+            k_vb: params.require("KVB")?,
+            c_cg: params.get_or(&["CCG"], 0.0)?,
+            c_pg: params.get_or(&["CGP", "CPG", "CPG1"], 0.0)?,
+            c_cp: params.get_or(&["CCP"], 0.0)?,
+            r_gi: params.get_or(&["RGI"], 2e3)?,
+            p_max: params.get_or(&["PMAX"], f64::INFINITY)?
+        };
+        Ok(match params.get(&["KG2"])?
+        {
+            Some(k_g2) => SpiceDevice::Pentode(PentodeParam { triode, k_g2 }),
+            None => SpiceDevice::Triode(triode)
+        })
+    }
+    #[cfg(not(feature = "tubes"))]
+    Err(SpiceError::UnsupportedDevice {
+        model: name.to_string(),
+        device: ".subckt".to_string()
+    })
+}
+
+macro_rules! from_str {
+    ($($feature:literal: $param:ident = $variant:ident),*) => {
+        $(
+            /// Reads the first matching model in a SPICE netlist.
+            #[cfg(feature = $feature)]
+            impl core::str::FromStr for $param
+            {
+                type Err = SpiceError;
+
+                fn from_str(netlist: &str) -> Result<Self, Self::Err>
+                {
+                    parse(netlist)?
+                        .into_iter()
+                        .find_map(|model| match model.device
+                        {
+                            SpiceDevice::$variant(param) => Some(param),
+                            #[allow(unreachable_patterns)]
+                            _ => None
+                        })
+                        .ok_or(SpiceError::NoModel)
+                }
+            }
+        )*
+    };
+}
+
+from_str!(
+    "diodes": DiodeParam = Diode,
+    "jfets": JFETParam = JFET,
+    "tubes": TriodeParam = Triode,
+    "tubes": PentodeParam = Pentode
+);
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[cfg(feature = "diodes")]
+    #[test]
+    fn diode()
+    {
+        use crate::{diodes::Diode1N914, DiodeClipper};
+
+        const NETLIST: &str = "
+* Fairchild 1N914
+.model D1N914 D(IS=25n RS=.6 N=1.752 CJO=4p
++ M=.333 VJ=.75 FC=.5 TT=20n) ; LTspice style comment
+";
+        let diode: DiodeParam = NETLIST.parse().unwrap();
+        assert_eq!(diode.i_0, 25e-9);
+        assert_eq!(diode.eta, 1.752);
+        assert_eq!(diode.r_s, 0.6);
+
+        // Two junctions in parallel
+        let double: DiodeParam = ".model D2 D(IS=25n RS=.6 N=1.752 AREA=2)".parse().unwrap();
+        assert_eq!(double.i_0, 50e-9);
+        assert_eq!(double.r_s, 0.3);
+
+        // Same as the preset, save the series resistance and nominal temperature
+        let clipper = DiodeClipper::<f64, _>::with_model(1e3, diode);
+        let preset = DiodeClipper::<f64, Diode1N914>::new(1e3);
+        for x in [-3.0, -0.5, 0.0, 0.5, 3.0]
+        {
+            assert!((clipper.saturate(x) - preset.saturate(x)).abs() < 0.05)
+        }
+    }
+
+    #[cfg(feature = "jfets")]
+    #[test]
+    fn jfet()
+    {
+        use crate::{jfets::JFET2N5457, JFETModelParam};
+
+        const NETLIST: &str = ".model 2N5457 NJF(Beta=1.125m Lambda=2.3m Vto=-1.372 Is=181.3f Isr=1.747p N=1 Nr=2
++ Xti=3 Cgd=2.2p M=.3622 Pb=1 Fc=.5 Cgs=2.2p Kf=3.4e-18 Af=1 mfg=Motorola)";
+
+        let jfet: JFETParam = NETLIST.parse().unwrap();
+        assert_eq!(
+            jfet,
+            JFETParam {
+                lambda: 2.3e-3,
+                ..JFET2N5457.jfet()
+            }
+        );
+
+        let double: JFETParam = ".model J2 NJF(Beta=1.125m Vto=-1.372 AREA=2)".parse().unwrap();
+        assert_eq!(double.beta, 2.25e-3);
+
+        // The full vendor card also has source and drain resistances, which change the drain current
+        assert_eq!(
+            ".model 2N5457 NJF(Beta=1.125m Rd=1 Rs=1 Vto=-1.372)".parse::<JFETParam>(),
+            Err(SpiceError::UnsupportedParameter {
+                model: "2N5457".to_string(),
+                parameter: "RD".to_string()
+            })
+        );
+    }
+
+    #[cfg(feature = "tubes")]
+    #[test]
+    fn tubes()
+    {
+        use crate::{tubes::{Tube12AU7, Tube6L6CG}, PentodeModelParam, TriodeModelParam};
+
+        const NETLIST: &str = "
+.SUBCKT 12AU7 1 2 3 ; P G K
++ PARAMS: MU=100 EX=1.4 KG1=1060 KP=600 KVB=300 RGI=2000
++ CCG=2.3P CGP=2.4P CCP=.9P PMAX=2.75
+E1 7 0 VALUE={V(1,3)/KP*LOG(1+EXP(KP*(1/MU+V(2,3)/SQRT(KVB+V(1,3)*V(1,3)))))}
+RE1 7 0 1G
+G1 1 3 VALUE={(PWR(V(7),EX)+PWRS(V(7),EX))/KG1}
+.ENDS
+
+.SUBCKT 6L6CG 1 2 3 4 ; P G2 G1 K
++ PARAMS: MU=21.5 EX=1.3 KG1=1180 KG2=4500 KP=84 KVB=300
++ CCG=2.3P CPG1=2.2P CCP=1P RGI=2000 PMAX=30
+.ENDS
+";
+        let triode: TriodeParam = NETLIST.parse().unwrap();
+        assert_eq!(triode, Tube12AU7.triode());
+
+        let pentode: PentodeParam = NETLIST.parse().unwrap();
+        assert_eq!(pentode, Tube6L6CG.pentode());
+    }
+
+    #[test]
+    fn errors()
+    {
+        assert!(matches!(
+            parse(".model Q2N3904 NPN(IS=6.734f BF=416.4)"),
+            Err(SpiceError::UnsupportedDevice { .. })
+        ));
+        #[cfg(feature = "diodes")]
+        assert_eq!(
+            parse(".model DX D(IS=1n FOO=2)"),
+            Err(SpiceError::UnsupportedParameter {
+                model: "DX".to_string(),
+                parameter: "FOO".to_string()
+            })
+        );
+        #[cfg(feature = "diodes")]
+        assert!(matches!(parse(".model DX D(IS=abc)"), Err(SpiceError::InvalidValue { .. })));
+        #[cfg(feature = "diodes")]
+        assert_eq!(
+            parse(".model DX D(IS=1n BV=100 IBV=5u)"),
+            Err(SpiceError::UnsupportedParameter {
+                model: "DX".to_string(),
+                parameter: "BV".to_string()
+            })
+        );
+        #[cfg(feature = "tubes")]
+        assert_eq!(
+            parse(".subckt T 1 2 3 PARAMS: MU=100 EX=1.4 KG1=1060 KVB=300\n.ends"),
+            Err(SpiceError::MissingParameter {
+                model: "T".to_string(),
+                parameter: "KP"
+            })
+        );
+        assert!(matches!(parse(".subckt T 1 2 3"), Err(SpiceError::Syntax { line: 1, .. })));
+    }
+
+    #[test]
+    fn numbers()
+    {
+        assert_eq!(number("2.52n"), Some(2.52e-9));
+        assert_eq!(number("1MEG"), Some(1e6));
+        assert_eq!(number("4pF"), Some(4e-12));
+        assert_eq!(number("-1.5e-3V"), Some(-1.5e-3));
+        assert_eq!(number(".5"), Some(0.5));
+        assert_eq!(number("1e"), Some(1.0));
+        assert_eq!(number("e3"), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use num::Float;
+
+use crate::f;
+
+/// Electron charge (Coulomb)
+pub const Q_E: f64 = 1.602176634e-19;
+/// Boltzmann constant (Joule per Kelvin)
+pub const K: f64 = 1.38e-23;
+/// Zero degrees Celsius (Kelvin)
+pub const ZERO_CELSIUS: f64 = 273.15;
+/// Room temperature (Kelvin)
+pub const T_ROOM: f64 = 20.0 + ZERO_CELSIUS;
+
+/// Thermal voltage `k*T/q` at temperature `t` (Kelvin)
+pub fn v_t<F>(t: F) -> F
+where
+    F: Float
+{
+    t*f!(K/Q_E)
+}
+
+/// Scales a saturation current `i_s` specified at temperature `t_nom` to the temperature `t` (Kelvin).
+///
+/// `I_S ∝ T^(XTI/η)*exp(-E_G/(η*k*T))`, which for the usual `XTI = 3` and `η = 1` is the textbook `I_S ∝ T³*exp(-E_G/(k*T))`.
+pub fn i_s<F>(i_s: F, eta: F, e_g: F, xti: F, t_nom: F, t: F) -> F
+where
+    F: Float
+{
+    let t_ratio = t/t_nom;
+    i_s*t_ratio.powf(xti/eta)*((t_ratio - F::one())*e_g/(eta*v_t(t))).exp()
+}
\ No newline at end of file
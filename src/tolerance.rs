@@ -0,0 +1,277 @@
+use num::Float;
+use real_time_fir_iir_filters::param::{FilterFloat, RC};
+
+use crate::{f, NoiseRng, PentodeClassA, PentodeModelParam, PentodeParam, TriodeClassA, TriodeModelParam, TriodeParam};
+
+/// Relative datasheet tolerances, each the largest deviation from the nominal value.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Tolerance
+{
+    /// Spread of the Koren parameters between tubes of the same type
+    pub tube: f64,
+    pub resistor: f64,
+    pub capacitor: f64
+}
+
+impl Tolerance
+{
+    /// Carbon composition resistors and paper capacitors, as found in vintage amps.
+    pub const VINTAGE: Self = Self {
+        tube: 0.15,
+        resistor: 0.1,
+        capacitor: 0.2
+    };
+    /// Metal film resistors and film capacitors.
+    pub const MODERN: Self = Self {
+        tube: 0.1,
+        resistor: 0.01,
+        capacitor: 0.05
+    };
+}
+
+impl Default for Tolerance
+{
+    fn default() -> Self
+    {
+        Self::MODERN
+    }
+}
+
+/// Draws component values scattered within their tolerances from a seed, so that each seed builds a different, but reproducible,
+/// instance of the same circuit.
+///
+/// Each value is drawn uniformly within its tolerance. Drawing the same components in the same order from the same seed always
+/// gives the same values.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ComponentSpread
+{
+    pub tolerance: Tolerance,
+    rng: NoiseRng
+}
+
+impl ComponentSpread
+{
+    pub fn new(tolerance: Tolerance, seed: u64) -> Self
+    {
+        Self {
+            tolerance,
+            rng: NoiseRng::new(seed)
+        }
+    }
+
+    fn scatter<F>(&mut self, x: F, tolerance: f64) -> F
+    where
+        F: Float
+    {
+        let u: f64 = self.rng.uniform();
+        x*f!(1.0 + tolerance*(u + u - 1.0))
+    }
+
+    pub fn resistor<F>(&mut self, r: F) -> F
+    where
+        F: Float
+    {
+        self.scatter(r, self.tolerance.resistor)
+    }
+    pub fn capacitor<F>(&mut self, c: F) -> F
+    where
+        F: Float
+    {
+        self.scatter(c, self.tolerance.capacitor)
+    }
+
+    /// A triode of the type `model`, with its amplification factor, emission and knee scattered.
+    pub fn triode<M>(&mut self, model: &M) -> TriodeParam
+    where
+        M: TriodeModelParam
+    {
+        let model = model.triode();
+        let tube = self.tolerance.tube;
+        TriodeParam {
+            mu: self.scatter(model.mu, tube),
+            k_g1: self.scatter(model.k_g1, tube),
+            k_p: self.scatter(model.k_p, tube),
+            k_vb: self.scatter(model.k_vb, tube),
+            ..model
+        }
+    }
+    /// A pentode of the type `model`, with its screen current scattered as well.
+    pub fn pentode<M>(&mut self, model: &M) -> PentodeParam
+    where
+        M: PentodeModelParam
+    {
+        let model = model.pentode();
+        PentodeParam {
+            triode: self.triode(&model.triode),
+            k_g2: self.scatter(model.k_g2, self.tolerance.tube)
+        }
+    }
+
+    /// Scatters the resistors of a stage. The voltages are left as they are.
+    pub fn triode_class_a<F>(&mut self, param: TriodeClassA<F>) -> TriodeClassA<F>
+    where
+        F: Float
+    {
+        TriodeClassA {
+            r_i: self.resistor(param.r_i),
+            r_p: self.resistor(param.r_p),
+            ..param
+        }
+    }
+    /// Scatters the resistors of a stage. The voltages are left as they are.
+    pub fn pentode_class_a<F>(&mut self, param: PentodeClassA<F>) -> PentodeClassA<F>
+    where
+        F: FilterFloat
+    {
+        PentodeClassA {
+            r_i: self.resistor(param.r_i),
+            r_p: self.resistor(param.r_p),
+            ..param
+        }
+    }
+    pub fn rc<F>(&mut self, param: RC<F>) -> RC<F>
+    where
+        F: Float
+    {
+        RC {
+            r: self.resistor(param.r),
+            c: self.capacitor(param.c)
+        }
+    }
+}
+
+impl TriodeParam
+{
+    /// The same tube after losing the fraction `age` of its cathode emission, which lowers the plate current at any operating point.
+    ///
+    /// `age` must lie in `[0, 1)`, a tube with no emission left conducting no current at all.
+    pub fn aged(self, age: f64) -> Self
+    {
+        assert!((0.0..1.0).contains(&age), "Age must lie in [0, 1)");
+        Self {
+            k_g1: self.k_g1/(1.0 - age),
+            ..self
+        }
+    }
+}
+
+impl PentodeParam
+{
+    /// The same tube after losing the fraction `age` of its cathode emission, which lowers both the plate and screen current.
+    ///
+    /// `age` must lie in `[0, 1)`.
+    pub fn aged(self, age: f64) -> Self
+    {
+        Self {
+            triode: self.triode.aged(age),
+            k_g2: self.k_g2/(1.0 - age)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::{f32::consts::TAU, ops::Range};
+
+    use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter};
+
+    use crate::{tubes::{Tube12AX7, Tube6L6CG}, Triode};
+
+    use super::*;
+
+    const V_P: f64 = 250.0;
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -4.0..0.0;
+
+        // Plate current in mA of a new tube, three scattered instances and a worn one
+        let new = Tube12AX7.triode();
+        let [t0, t1, t2] = [0, 1, 2].map(|seed| ComponentSpread::new(Tolerance::VINTAGE, seed).triode(&Tube12AX7));
+        let worn = new.aged(0.4);
+
+        crate::tests::plot(
+            "ComponentSpread",
+            RANGE,
+            |v_g| [new, t0, t1, t2, worn].map(|model| model.i_p(v_g as f64, V_P) as f32*1e3)
+        )
+    }
+
+    #[test]
+    fn spread()
+    {
+        const N: u64 = 64;
+
+        let nominal = Tube6L6CG.pentode();
+        let tolerance = Tolerance::default();
+
+        // Reproducible for the same seed, different between seeds, but always within tolerance
+        assert_eq!(ComponentSpread::new(tolerance, 7).pentode(&Tube6L6CG), ComponentSpread::new(tolerance, 7).pentode(&Tube6L6CG));
+        assert_ne!(ComponentSpread::new(tolerance, 7).pentode(&Tube6L6CG), ComponentSpread::new(tolerance, 8).pentode(&Tube6L6CG));
+        for seed in 0..N
+        {
+            let mut spread = ComponentSpread::new(tolerance, seed);
+
+            let pentode = spread.pentode(&Tube6L6CG);
+            for (x, nominal) in [
+                (pentode.triode.mu, nominal.triode.mu),
+                (pentode.triode.k_g1, nominal.triode.k_g1),
+                (pentode.triode.k_p, nominal.triode.k_p),
+                (pentode.triode.k_vb, nominal.triode.k_vb),
+                (pentode.k_g2, nominal.k_g2)
+            ]
+            {
+                assert!((x/nominal - 1.0).abs() <= tolerance.tube);
+            }
+            assert_eq!(pentode.triode.ex, nominal.triode.ex);
+
+            let rc = spread.rc(RC {r: 1e3, c: 1e-6});
+            assert!((rc.r/1e3 - 1.0).abs() <= tolerance.resistor as f32 + f32::EPSILON);
+            assert!((rc.c/1e-6 - 1.0).abs() <= tolerance.capacitor as f32 + f32::EPSILON);
+        }
+
+        // Aging lowers the emission
+        let v_g = -1.0;
+        let new = Tube12AX7.triode();
+        assert!(new.aged(0.3).i_p(v_g, V_P) < new.i_p(v_g, V_P));
+        assert_eq!(new.aged(0.0), new);
+        let pentode = nominal.aged(0.3);
+        assert!(pentode.i_p(v_g, V_P, V_P) < nominal.i_p(v_g, V_P, V_P));
+        assert!(pentode.i_g2(v_g, V_P) < nominal.i_g2(v_g, V_P));
+    }
+
+    #[test]
+    fn two_amps()
+    {
+        const RATE: f32 = 48000.0;
+        const N: usize = 1024;
+
+        let param = TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 1.5
+        };
+        let cathode = RC {
+            r: 1.5e3,
+            c: 22e-6
+        };
+
+        // The same circuit, built twice
+        let render = |seed| {
+            let mut spread = ComponentSpread::new(Tolerance::VINTAGE, seed);
+            let model = spread.triode(&Tube12AX7);
+            let mut triode = Triode::<_, _, FirstOrderRCFilter<LowPass, _>, FirstOrderRCFilter<LowPass, _>, FirstOrderRCFilter<LowPass, _>, _>::new(
+                spread.triode_class_a(param),
+                model,
+                spread.rc(cathode)
+            );
+            core::array::from_fn::<_, N, _>(|n| triode.saturate(RATE, (TAU*440.0*n as f32/RATE).sin()))
+        };
+
+        assert_eq!(render(1), render(1));
+        assert_ne!(render(1), render(2));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,195 @@
+use real_time_fir_iir_filters::param::{FilterFloat, FilterParam};
+
+use crate::f;
+
+use super::poly_mul;
+
+/// Parameters of an active Baxandall bass and treble control around an inverting op-amp.
+///
+/// <pre>
+/// X--o--[R_b]--o--[P_b bass]--o--[R_b]--o--Y
+///    |         |      |       |         |
+///    |         o-[C_b]o[C_b]--o         |
+///    |                |                 |
+///    |                o-------[-\       |
+///    |                |       [  >------o
+///    |                |  ⏚----[+/       |
+///    |                |                 |
+///    o[C_t][R_t][P_t treble][R_t]--[C_t]o
+/// </pre>
+///
+/// Both wipers meet at the inverting input of the op-amp. On each side, the branches are a resistor and a bypassed section of the bass pot,
+/// and a capacitor, resistor and section of the treble pot.
+///
+/// The bass and treble networks are taken as parallel branches from the input and the output to the virtual ground, ignoring how they load
+/// each other. The inversion of the op-amp is left out.
+///
+/// Every pot position goes from 0 to 1, with 0.5 being flat. The positions are linear, so any taper must be applied beforehand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BaxandallToneStackParam<F>
+where
+    F: FilterFloat
+{
+    pub treble: F,
+    pub bass: F,
+    /// Bass pot
+    pub p_b: F,
+    /// Resistors on either side of the bass pot
+    pub r_b: F,
+    /// Capacitors bypassing either side of the bass pot
+    pub c_b: F,
+    /// Treble pot
+    pub p_t: F,
+    /// Resistors on either side of the treble pot
+    pub r_t: F,
+    /// Capacitors on either side of the treble pot
+    pub c_t: F
+}
+
+impl<F> BaxandallToneStackParam<F>
+where
+    F: FilterFloat
+{
+    /// Roughly ±20dB of boost and cut, shelving below about 500Hz and above about 5kHz.
+    pub fn new(treble: F, bass: F) -> Self
+    {
+        Self {
+            treble,
+            bass,
+            p_b: f!(100e3),
+            r_b: f!(10e3),
+            c_b: f!(33e-9),
+            p_t: f!(100e3),
+            r_t: f!(1e3),
+            c_t: f!(3.3e-9)
+        }
+    }
+
+    /// Coefficients of the analog transfer function, in ascending powers of `s`.
+    fn s_coeffs(&self) -> ([F; 5], [F; 5])
+    {
+        /// Keeps the wipers off the ends of the bass pot, where a pole and a zero would cancel on the unit circle
+        const POT_END: f64 = 1e-3;
+
+        let Self {treble, bass, p_b, r_b, c_b, p_t, r_t, c_t} = *self;
+
+        let one = F::one();
+        let bass = bass.max(f!(POT_END)).min(f!(1.0 - POT_END));
+        let treble = treble.max(F::zero()).min(one);
+
+        // Admittance from one side to the virtual ground, as a numerator and denominator
+        let branch = |bass: F, treble: F| {
+            let p_b = bass*p_b;
+            let n_b = [one, p_b*c_b];
+            let d_b = [r_b + p_b, r_b*p_b*c_b];
+            let n_t = [F::zero(), c_t];
+            let d_t = [one, (r_t + treble*p_t)*c_t];
+
+            let n_bt: [F; 3] = poly_mul(&n_b, &d_t);
+            let n_tb: [F; 3] = poly_mul(&n_t, &d_b);
+            (
+                core::array::from_fn::<_, 3, _>(|i| n_bt[i] + n_tb[i]),
+                poly_mul::<_, 3>(&d_b, &d_t)
+            )
+        };
+        let (n_i, d_i) = branch(one - bass, one - treble);
+        let (n_f, d_f) = branch(bass, treble);
+
+        (poly_mul(&n_i, &d_f), poly_mul(&d_i, &n_f))
+    }
+}
+
+impl<F> FilterParam for BaxandallToneStackParam<F>
+where
+    F: FilterFloat
+{
+    const ORDER: usize = 4;
+
+    type F = F;
+}
+
+real_time_fir_iir_filters::def_rtf!(
+    {
+        /// The active Baxandall tone stack, discretized with the bilinear transform.
+        ///
+        /// The coefficients are recomputed on the next sample whenever the knobs are moved through `get_param_mut`.
+    }
+    BaxandallToneStack
+    {
+        type Param = BaxandallToneStackParam;
+
+        const OUTPUTS: usize = 1;
+        const OUTPUT_BUFS: usize = 1;
+        const SOS_BUFS: usize = 1;
+        const SOS_STAGES: usize = 0;
+        const ORDER: usize = 4;
+        const IS_IIR: bool = true;
+
+        fn make_coeffs(param, rate) -> _
+        {
+            let (b, a) = param.s_coeffs();
+            (
+                ([], [], [
+                    crate::bilinear(rate, b)
+                ]),
+                [([], [
+                    crate::bilinear(rate, a)
+                ])]
+            )
+        }
+    }
+);
+
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use real_time_fir_iir_filters::rtf::{Rtf, StaticRtf};
+
+    use super::*;
+
+    const RATE: f32 = 48000.0;
+
+    #[test]
+    fn it_works()
+    {
+        // Frequency in decades
+        const RANGE: Range<f32> = 1.0..4.3;
+
+        let mut f0 = BaxandallToneStack::new(BaxandallToneStackParam::new(0.5, 0.5));
+        let mut f1 = BaxandallToneStack::new(BaxandallToneStackParam::new(1.0, 1.0));
+        let mut f2 = BaxandallToneStack::new(BaxandallToneStackParam::new(0.0, 0.0));
+        let mut f3 = BaxandallToneStack::new(BaxandallToneStackParam::new(1.0, 0.0));
+
+        crate::tests::plot(
+            "BaxandallToneStack",
+            RANGE,
+            |x| {
+                let freq = 10.0f32.powf(x);
+                [&mut f0, &mut f1, &mut f2, &mut f3].map(|f| super::super::gain(f, RATE, freq))
+            }
+        )
+    }
+
+    #[test]
+    fn knobs()
+    {
+        let mut filter = BaxandallToneStack::new(BaxandallToneStackParam::new(0.5, 0.5));
+
+        // Flat when centered
+        for freq in [20.0, 1e3, 20e3]
+        {
+            assert!(super::super::gain(&mut filter, RATE, freq).abs() < 1e-2);
+        }
+
+        filter.get_param_mut().bass = 1.0;
+        let [y] = filter.filter(RATE, 1.0);
+        assert!(y.is_finite());
+        assert!(super::super::gain(&mut filter, RATE, 30.0) > 15.0);
+
+        filter.get_param_mut().treble = 0.0;
+        assert!(super::super::gain(&mut filter, RATE, 15e3) < -10.0);
+    }
+}
\ No newline at end of file
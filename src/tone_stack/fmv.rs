@@ -0,0 +1,222 @@
+use real_time_fir_iir_filters::param::{FilterFloat, FilterParam};
+
+use crate::f;
+
+/// Parameters of the passive three-knob tone stack found in Fender, Marshall and Vox amps.
+///
+/// <pre>
+/// X---o---[C₁]----o
+///     |           |
+///     |      [R₁ treble]<---Y
+///     |           |
+///     o-[R₄]-o-[C₂]-o
+///            |      |
+///            |  [R₂ bass]
+///            |      |
+///            o-[C₃]-o
+///                   |
+///               [R₃ mid]
+///                   |
+///                   ⏚
+/// </pre>
+///
+/// Every pot position goes from 0 to 1, with 1 being the most boost. The positions are linear, so any taper must be applied beforehand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FMVToneStackParam<F>
+where
+    F: FilterFloat
+{
+    pub treble: F,
+    pub mid: F,
+    pub bass: F,
+    /// Treble pot
+    pub r_1: F,
+    /// Bass pot
+    pub r_2: F,
+    /// Mid pot
+    pub r_3: F,
+    /// Slope resistor
+    pub r_4: F,
+    /// Treble capacitor
+    pub c_1: F,
+    /// Bass capacitor
+    pub c_2: F,
+    /// Mid capacitor
+    pub c_3: F
+}
+
+impl<F> FMVToneStackParam<F>
+where
+    F: FilterFloat
+{
+    /// The tone stack of the Fender Bassman 5F6-A.
+    pub fn bassman(treble: F, mid: F, bass: F) -> Self
+    {
+        Self {
+            treble,
+            mid,
+            bass,
+            r_1: f!(250e3),
+            r_2: f!(1e6),
+            r_3: f!(25e3),
+            r_4: f!(56e3),
+            c_1: f!(250e-12),
+            c_2: f!(20e-9),
+            c_3: f!(20e-9)
+        }
+    }
+
+    /// The tone stack of the Marshall JCM800.
+    pub fn jcm800(treble: F, mid: F, bass: F) -> Self
+    {
+        Self {
+            treble,
+            mid,
+            bass,
+            r_1: f!(220e3),
+            r_2: f!(1e6),
+            r_3: f!(22e3),
+            r_4: f!(33e3),
+            c_1: f!(470e-12),
+            c_2: f!(22e-9),
+            c_3: f!(22e-9)
+        }
+    }
+
+    /// Coefficients of the analog transfer function, in ascending powers of `s`.
+    ///
+    /// From D. T. Yeh and J. O. Smith, "Discretization of the '59 Fender Bassman tone stack" (DAFx 2006).
+    fn s_coeffs(&self) -> ([F; 4], [F; 4])
+    {
+        let Self {treble: t, mid: m, bass: l, r_1, r_2, r_3, r_4, c_1, c_2, c_3} = *self;
+
+        let b1 = t*c_1*r_1 + m*c_3*r_3 + l*(c_1*r_2 + c_2*r_2) + (c_1*r_3 + c_2*r_3);
+        let b2 = t*(c_1*c_2*r_1*r_4 + c_1*c_3*r_1*r_4)
+            - m*m*(c_1*c_3*r_3*r_3 + c_2*c_3*r_3*r_3)
+            + m*(c_1*c_3*r_1*r_3 + c_1*c_3*r_3*r_3 + c_2*c_3*r_3*r_3)
+            + l*(c_1*c_2*r_1*r_2 + c_1*c_2*r_2*r_4 + c_1*c_3*r_2*r_4)
+            + l*m*(c_1*c_3*r_2*r_3 + c_2*c_3*r_2*r_3)
+            + (c_1*c_2*r_1*r_3 + c_1*c_2*r_3*r_4 + c_1*c_3*r_3*r_4);
+        let c_123 = c_1*c_2*c_3;
+        let b3 = l*m*c_123*(r_1*r_2*r_3 + r_2*r_3*r_4)
+            - m*m*c_123*(r_1*r_3*r_3 + r_3*r_3*r_4)
+            + m*c_123*(r_1*r_3*r_3 + r_3*r_3*r_4)
+            + t*c_123*r_1*r_3*r_4
+            - t*m*c_123*r_1*r_3*r_4
+            + t*l*c_123*r_1*r_2*r_4;
+
+        let a0 = F::one();
+        let a1 = (c_1*r_1 + c_1*r_3 + c_2*r_3 + c_2*r_4 + c_3*r_4) + m*c_3*r_3 + l*(c_1*r_2 + c_2*r_2);
+        let a2 = m*(c_1*c_3*r_1*r_3 - c_2*c_3*r_3*r_4 + c_1*c_3*r_3*r_3 + c_2*c_3*r_3*r_3)
+            + l*m*(c_1*c_3*r_2*r_3 + c_2*c_3*r_2*r_3)
+            - m*m*(c_1*c_3*r_3*r_3 + c_2*c_3*r_3*r_3)
+            + l*(c_1*c_2*r_2*r_4 + c_1*c_2*r_1*r_2 + c_1*c_3*r_2*r_4 + c_2*c_3*r_2*r_4)
+            + (c_1*c_2*r_1*r_4 + c_1*c_3*r_1*r_4 + c_1*c_2*r_3*r_4 + c_1*c_2*r_1*r_3 + c_1*c_3*r_3*r_4 + c_2*c_3*r_3*r_4);
+        let a3 = l*m*c_123*(r_1*r_2*r_3 + r_2*r_3*r_4)
+            - m*m*c_123*(r_1*r_3*r_3 + r_3*r_3*r_4)
+            + m*c_123*(r_3*r_3*r_4 + r_1*r_3*r_3 - r_1*r_3*r_4)
+            + l*c_123*r_1*r_2*r_4
+            + c_123*r_1*r_3*r_4;
+
+        ([F::zero(), b1, b2, b3], [a0, a1, a2, a3])
+    }
+}
+
+impl<F> FilterParam for FMVToneStackParam<F>
+where
+    F: FilterFloat
+{
+    const ORDER: usize = 3;
+
+    type F = F;
+}
+
+real_time_fir_iir_filters::def_rtf!(
+    {
+        /// The FMV tone stack, discretized with the bilinear transform.
+        ///
+        /// The coefficients are recomputed on the next sample whenever the knobs are moved through `get_param_mut`.
+    }
+    FMVToneStack
+    {
+        type Param = FMVToneStackParam;
+
+        const OUTPUTS: usize = 1;
+        const OUTPUT_BUFS: usize = 1;
+        const SOS_BUFS: usize = 1;
+        const SOS_STAGES: usize = 0;
+        const ORDER: usize = 3;
+        const IS_IIR: bool = true;
+
+        fn make_coeffs(param, rate) -> _
+        {
+            let (b, a) = param.s_coeffs();
+            (
+                ([], [], [
+                    crate::bilinear(rate, b)
+                ]),
+                [([], [
+                    crate::bilinear(rate, a)
+                ])]
+            )
+        }
+    }
+);
+
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use real_time_fir_iir_filters::rtf::{Rtf, StaticRtf};
+
+    use super::*;
+
+    const RATE: f32 = 48000.0;
+
+    #[test]
+    fn it_works()
+    {
+        // Frequency in decades
+        const RANGE: Range<f32> = 1.0..4.3;
+
+        let mut f0 = FMVToneStack::new(FMVToneStackParam::bassman(0.5, 0.5, 0.5));
+        let mut f1 = FMVToneStack::new(FMVToneStackParam::bassman(1.0, 0.0, 1.0));
+        let mut f2 = FMVToneStack::new(FMVToneStackParam::bassman(0.0, 1.0, 0.0));
+        let mut f3 = FMVToneStack::new(FMVToneStackParam::jcm800(0.5, 0.5, 0.5));
+
+        crate::tests::plot(
+            "FMVToneStack",
+            RANGE,
+            |x| {
+                let freq = 10.0f32.powf(x);
+                [&mut f0, &mut f1, &mut f2, &mut f3].map(|f| super::super::gain(f, RATE, freq))
+            }
+        )
+    }
+
+    #[test]
+    fn knobs()
+    {
+        const FREQS: [f32; 3] = [60.0, 500.0, 5e3];
+
+        let mut filter = FMVToneStack::new(FMVToneStackParam::bassman(0.0, 0.0, 0.0));
+
+        // Each knob boosts its own band, taking effect while the filter is running
+        for (i, freq) in FREQS.into_iter().enumerate()
+        {
+            let g = super::super::gain(&mut filter, RATE, freq);
+            let param = filter.get_param_mut();
+            match i
+            {
+                0 => param.bass = 1.0,
+                1 => param.mid = 1.0,
+                _ => param.treble = 1.0
+            }
+            let [y] = filter.filter(RATE, 1.0);
+            assert!(y.is_finite());
+            assert!(super::super::gain(&mut filter, RATE, freq) > g + 1.0);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+moddef::moddef!(
+    flat(pub) mod {
+        baxandall,
+        fmv
+    }
+);
+
+fn poly_mul<F, const N: usize>(a: &[F], b: &[F]) -> [F; N]
+where
+    F: FilterFloat
+{
+    let mut c = [F::zero(); N];
+    for (i, &a) in a.iter().enumerate()
+    {
+        for (j, &b) in b.iter().enumerate()
+        {
+            c[i + j] = c[i + j] + a*b;
+        }
+    }
+    c
+}
+
+/// Magnitude response in dB at a given frequency.
+#[cfg(test)]
+fn gain<T>(filter: &mut T, rate: f32, freq: f32) -> f32
+where
+    T: real_time_fir_iir_filters::rtf::Rtf<F = f32, Outputs<num::Complex<f32>> = [num::Complex<f32>; 1]>
+{
+    let [h] = filter.frequency_response(rate, core::f32::consts::TAU*freq/rate);
+    20.0*h.norm().log10()
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, BiasPoint};
+
+use super::{TriodeClassA, TriodeModelParam};
+
+impl<F> TriodeClassA<F>
+where
+    F: FilterFloat
+{
+    /// Solves the quiescent point of the stage when self-biased by the cathode resistor `r_k`.
+    ///
+    /// The supply voltage `v_pp` is taken relative to ground, so the plate sees the supply minus the cathode voltage.
+    pub fn bias<M>(&self, model: &M, r_k: F) -> BiasPoint<F>
+    where
+        M: TriodeModelParam
+    {
+        const NEWTON: usize = 32;
+        const DELTA: f64 = 1e-6;
+
+        let TriodeClassA {r_p, v_pp, ..} = *self;
+        let model = model.triode();
+
+        // Plate-cathode voltage and plate current on the load line, for a given cathode voltage
+        let v_p_i_p = |v_k: F| {
+            let v_pk = v_pp - v_k;
+            // The plate current rises with the plate voltage while the load current falls, so g is increasing
+            let g = |v_p: F| model.i_p(-v_k, v_p) - (v_pk - v_p)/r_p;
+            let v_p = crate::newton_bracketed(g, v_pk, [F::zero(), v_pk.max(F::zero())], v_pp*f!(DELTA), NEWTON);
+            [v_p, (v_pk - v_p)/r_p]
+        };
+
+        // Raising the cathode starves the triode, so the current falls and f is increasing
+        let f = |v_k: F| {
+            let [_, i_p] = v_p_i_p(v_k);
+            v_k - r_k*i_p
+        };
+        let v_k = crate::newton_bracketed(f, F::zero(), [F::zero(), v_pp], v_pp*f!(DELTA), NEWTON);
+        let [v_p, i_p] = v_p_i_p(v_k);
+
+        BiasPoint {
+            v_k,
+            v_p: v_k + v_p,
+            i_p,
+            i_k: i_p,
+            p_p: v_p*i_p,
+            p_max: f!(model.p_max)
+        }
+    }
+
+    /// The stage at the quiescent point `bias`, as `Triode` takes it: with `v_c` at the cathode voltage, and `v_pp` lowered by it
+    /// to the plate-cathode supply.
+    pub fn with_bias(self, bias: &BiasPoint<F>) -> Self
+    {
+        Self {
+            v_pp: self.v_pp - bias.v_k,
+            v_c: bias.v_k,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::{tubes::{Tube12AU7, Tube12AX7}, Triode};
+
+    use super::*;
+
+    fn param() -> TriodeClassA<f32>
+    {
+        TriodeClassA {
+            r_i: 1e3,
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 0.0
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        // Cathode resistor in kΩ
+        const RANGE: Range<f32> = 0.1..10.0;
+
+        // Cathode voltage, plate voltage in hectovolts, plate current in mA and dissipation in W
+        crate::tests::plot(
+            "TriodeBias",
+            RANGE,
+            |r_k| {
+                let bias = param().bias(&Tube12AX7, r_k*1e3);
+                [bias.v_k, bias.v_p*1e-2, bias.i_p*1e3, bias.p_p]
+            }
+        )
+    }
+
+    #[test]
+    fn quiescent()
+    {
+        const R_K: f32 = 1.5e3;
+
+        let supply = param();
+        let bias = supply.bias(&Tube12AX7, R_K);
+        let param = supply.with_bias(&bias);
+
+        // Only the quiescent point is solved, the supply is left as it is
+        assert_eq!(supply.bias(&Tube12AX7, R_K), bias);
+
+        // Ohm's law across the cathode resistor and the plate resistor
+        assert_eq!(param.v_c, bias.v_k);
+        assert_eq!(param.v_pp, supply.v_pp - bias.v_k);
+        assert!((bias.v_k - R_K*bias.i_k).abs() < 1e-3*bias.v_k);
+        assert!((supply.v_pp - bias.v_p - param.r_p*bias.i_p).abs() < 1e-3*supply.v_pp);
+
+        // A stage built from the biased parameters idles at the same point
+        let triode = Triode::<_, Tube12AX7, (), (), (), _>::new(param, Tube12AX7, ());
+        assert!((triode.i_p() - bias.i_p).abs() < 1e-3*bias.i_p);
+
+        // The grid bias reproduces the plate current
+        let i_p = Tube12AX7.triode().i_p(-bias.v_k, bias.v_p - bias.v_k);
+        assert!((i_p - bias.i_p).abs() < 1e-3*bias.i_p);
+
+        assert!(bias.p_p > 0.0 && !bias.is_overdissipated());
+
+        // A larger cathode resistor biases colder
+        let colder = supply.bias(&Tube12AX7, 2.0*R_K);
+        assert!(colder.v_k > bias.v_k && colder.i_p < bias.i_p);
+
+        // Run hot into a small plate resistor, the plate exceeds its rating
+        let hot = TriodeClassA {
+            r_p: 1e3,
+            v_pp: 450.0,
+            ..supply
+        }.bias(&Tube12AU7, 100.0);
+        assert!(hot.is_overdissipated() && hot.dissipation_ratio() > 1.0);
+    }
+}
\ No newline at end of file
@@ -3,46 +3,95 @@ use alloc::alloc::{Allocator, Global};
 
 use real_time_fir_iir_filters::param::FilterFloat;
 
-use crate::CacheTable;
+use crate::{f, CacheTable, HermiteTable, Interpolation};
 
-use super::{calc::TriodeCalc, TriodeClassA, TriodeModel};
+use super::{calc::TriodeCalc, TriodeClassA, TriodeModelParam, TriodeParam};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 struct TriodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     param: TriodeClassA<F>,
+    model: Option<TriodeParam>,
     marker: PhantomData<M>
 }
 impl<F, M> FnOnce<(F,)> for TriodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     type Output = [F; 2];
 
     extern "rust-call" fn call_once(mut self, (vg,): (F,)) -> Self::Output
     {
-        TriodeCalc::<F, M>::vp_a(&mut self.param, vg)
+        self.call_mut((vg,))
     }
 }
 impl<F, M> FnMut<(F,)> for TriodeCacheFunc<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     extern "rust-call" fn call_mut(&mut self, (vg,): (F,)) -> Self::Output
     {
-        TriodeCalc::<F, M>::vp_a(&mut self.param, vg)
+        match self.model
+        {
+            Some(model) => TriodeCalc::<F, TriodeParam>::vp_a(&mut self.param, &model, vg),
+            None => [F::nan(); 2]
+        }
+    }
+}
+/// Relative change of the supply voltage before a cache following a `PowerSupply` rebuilds its table.
+const V_PP_HYSTERESIS: f64 = 0.02;
+
+/// A table of the plate voltage, filled by a `TriodeCacheFunc` and refreshed whenever the model changes.
+trait TriodeTable<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    fn cache_func(&self) -> &TriodeCacheFunc<F, M>;
+    fn cache_func_mut(&mut self) -> &mut TriodeCacheFunc<F, M>;
+
+    fn set_model(&mut self, model: &M)
+    {
+        let model = Some(model.triode());
+        if self.cache_func().model != model
+        {
+            self.cache_func_mut().model = model;
+        }
+    }
+
+    /// Rebuilds the table lazily, only once the supply has left the band around the voltage the table was built for.
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        let v_pp_table = self.cache_func().param.v_pp;
+        (v_pp - v_pp_table).abs() > f!(V_PP_HYSTERESIS)*v_pp_table.abs()
+            && TriodeCalc::<F, M>::track_v_pp(&mut self.cache_func_mut().param, v_pp)
+    }
+}
+impl<F, M, A> TriodeTable<F, M> for CacheTable<F, 2, TriodeCacheFunc<F, M>, A>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    A: Allocator + Clone
+{
+    fn cache_func(&self) -> &TriodeCacheFunc<F, M>
+    {
+        self.func()
+    }
+    fn cache_func_mut(&mut self) -> &mut TriodeCacheFunc<F, M>
+    {
+        self.func_mut()
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TriodeCache<F, M, A = Global>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     A: Allocator + Clone
 {
     cache: CacheTable<F, 2, TriodeCacheFunc<F, M>, A>
@@ -50,7 +99,7 @@ where
 impl<F, M> TriodeCache<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     pub fn new(param: TriodeClassA<F>, range: Range<F>, resolution: usize) -> Self
     {
@@ -60,7 +109,7 @@ where
 impl<F, M, A> TriodeCache<F, M, A>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     A: Allocator + Clone
 {
     pub fn new_in(param: TriodeClassA<F>, range: Range<F>, resolution: usize, alloc: A) -> Self
@@ -69,6 +118,7 @@ where
             cache: CacheTable::new_in(
                 TriodeCacheFunc {
                     param,
+                    model: None,
                     marker: PhantomData
                 },
                 range,
@@ -77,23 +127,173 @@ where
             )
         }
     }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self
+    {
+        self.set_interpolation(interpolation);
+        self
+    }
+
+    pub fn interpolation(&self) -> Interpolation
+    {
+        self.cache.interpolation()
+    }
+    pub fn set_interpolation(&mut self, interpolation: Interpolation)
+    {
+        self.cache.set_interpolation(interpolation)
+    }
+
+    /// The largest deviation of the plate voltage and the gain from the exact solution, checked at `oversampling` points inside each
+    /// cell of the table.
+    pub fn worst_case_error(&mut self, model: &M, oversampling: usize) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        self.cache.worst_case_error(oversampling)
+    }
 }
 impl<F, M, A> TriodeCalc<F, M> for TriodeCache<F, M, A>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     A: Allocator + Clone
 {
     fn param(&self) -> &TriodeClassA<F>
     {
-        &self.cache.func().param
+        &self.cache.cache_func().param
     }
     fn param_mut(&mut self) -> &mut TriodeClassA<F>
     {
-        &mut self.cache.func_mut().param
+        &mut self.cache.cache_func_mut().param
     }
-    fn vp_a(&mut self, vg: F) -> [F; 2]
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
     {
+        self.cache.set_model(model);
         self.cache.saturate(vg)
     }
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        self.cache.track_v_pp(v_pp)
+    }
+}
+
+/// Plate voltage and its derivative with respect to the grid voltage, which is the negated gain.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct TriodeSlopeFunc<F, M>(TriodeCacheFunc<F, M>)
+where
+    F: FilterFloat,
+    M: TriodeModelParam;
+impl<F, M> FnOnce<(F,)> for TriodeSlopeFunc<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    type Output = [F; 2];
+
+    extern "rust-call" fn call_once(mut self, (vg,): (F,)) -> Self::Output
+    {
+        self.call_mut((vg,))
+    }
+}
+impl<F, M> FnMut<(F,)> for TriodeSlopeFunc<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    extern "rust-call" fn call_mut(&mut self, (vg,): (F,)) -> Self::Output
+    {
+        let [vp, a] = self.0.call_mut((vg,));
+        [vp, -a]
+    }
+}
+impl<F, M, A> TriodeTable<F, M> for HermiteTable<F, TriodeSlopeFunc<F, M>, A>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    A: Allocator + Clone
+{
+    fn cache_func(&self) -> &TriodeCacheFunc<F, M>
+    {
+        &self.func().0
+    }
+    fn cache_func_mut(&mut self) -> &mut TriodeCacheFunc<F, M>
+    {
+        &mut self.func_mut().0
+    }
+}
+/// A cache of the plate voltage interpolated by a `HermiteTable`, using the gain of the model as its slope.
+///
+/// Both the plate voltage and the gain are continuous between the cells of the table, so the Miller effect does not step as the grid
+/// voltage sweeps across them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriodeHermiteCache<F, M, A = Global>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    A: Allocator + Clone
+{
+    cache: HermiteTable<F, TriodeSlopeFunc<F, M>, A>
+}
+impl<F, M> TriodeHermiteCache<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    pub fn new(param: TriodeClassA<F>, range: Range<F>, resolution: usize) -> Self
+    {
+        Self::new_in(param, range, resolution, Global)
+    }
+}
+impl<F, M, A> TriodeHermiteCache<F, M, A>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    A: Allocator + Clone
+{
+    pub fn new_in(param: TriodeClassA<F>, range: Range<F>, resolution: usize, alloc: A) -> Self
+    {
+        Self {
+            cache: HermiteTable::new_in(
+                TriodeSlopeFunc(TriodeCacheFunc {
+                    param,
+                    model: None,
+                    marker: PhantomData
+                }),
+                range,
+                resolution,
+                alloc
+            )
+        }
+    }
+
+    /// As `TriodeCache::worst_case_error`.
+    pub fn worst_case_error(&mut self, model: &M, oversampling: usize) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        self.cache.worst_case_error(oversampling)
+    }
+}
+impl<F, M, A> TriodeCalc<F, M> for TriodeHermiteCache<F, M, A>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    A: Allocator + Clone
+{
+    fn param(&self) -> &TriodeClassA<F>
+    {
+        &self.cache.cache_func().param
+    }
+    fn param_mut(&mut self) -> &mut TriodeClassA<F>
+    {
+        &mut self.cache.cache_func_mut().param
+    }
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
+    {
+        self.cache.set_model(model);
+        let [vp, dvp] = self.cache.saturate(vg);
+        [vp, -dvp]
+    }
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        self.cache.track_v_pp(v_pp)
+    }
 }
\ No newline at end of file
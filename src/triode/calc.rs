@@ -1,13 +1,13 @@
 use real_time_fir_iir_filters::param::FilterFloat;
 
-use super::{TriodeClassA, TriodeModel};
+use super::{TriodeClassA, TriodeModelParam};
 
 use crate::f;
 
 pub trait TriodeCalc<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     fn reset(&mut self)
     {
@@ -15,12 +15,23 @@ where
     }
     fn param(&self) -> &TriodeClassA<F>;
     fn param_mut(&mut self) -> &mut TriodeClassA<F>;
-    fn vp_a(&mut self, vg: F) -> [F; 2];
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2];
+
+    /// Follows a supply voltage changing while running, returning whether the plate solve has changed.
+    fn track_v_pp(&mut self, v_pp: F) -> bool
+    {
+        let changed = self.param().v_pp != v_pp;
+        if changed
+        {
+            self.param_mut().v_pp = v_pp;
+        }
+        changed
+    }
 }
 impl<F, M> TriodeCalc<F, M> for TriodeClassA<F>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     fn param(&self) -> &TriodeClassA<F>
     {
@@ -30,19 +41,21 @@ where
     {
         self
     }
-    fn vp_a(&mut self, vg: F) -> [F; 2]
+    fn vp_a(&mut self, model: &M, vg: F) -> [F; 2]
     {
         let TriodeClassA {r_i: _, r_p: rp, v_pp: vpp, v_c: _} = *self;
         let two_rp = rp + rp;
         let one = F::one();
         let zero = F::zero();
 
-        let mu_inv = f!(1.0/M::MU);
-        let mu = f!(M::MU);
-        let kp = f!(M::K_P);
-        let kvb = f!(M::K_VB);
-        let kg1 = f!(M::K_G1);
-        let ex = f!(M::EX);
+        let model = model.triode();
+
+        let mu_inv = f!(1.0/model.mu);
+        let mu = f!(model.mu);
+        let kp = f!(model.k_p);
+        let kvb = f!(model.k_vb);
+        let kg1 = f!(model.k_g1);
+        let ex = f!(model.ex);
 
         let v1_max = (vpp*kg1/two_rp).powf(ex.recip());
         
@@ -70,11 +83,14 @@ where
             vpp/(kp/b + two_rp/kg1)
         };
 
-        const NEWTON: usize = 2;
+        const NEWTON: usize = 16;
+
+        // f is increasing in v1, so a Newton step leaving the bracket falls back to bisection
+        let mut bracket = [zero, v1_max];
+        v1 = v1.max(zero).min(v1_max);
 
         for _ in 0..NEWTON
         {
-            v1 = v1.max(zero).min(v1_max);
             let vp = (vpp - two_rp/kg1*v1.powf(ex)).max(zero);
             let dvp_dv1 = -two_rp/kg1*ex*v1.powf(ex - one);
 
@@ -90,8 +106,25 @@ where
             let f = v1 - vp*c/kp;
             let df_dv1 = one - dvp_dv1*c/kp - dc_dv1_d_kp*vp;
 
+            if f > zero
+            {
+                bracket[1] = v1
+            }
+            else
+            {
+                bracket[0] = v1
+            }
+
             let delta = f/df_dv1;
+            if delta.abs() <= v1_max*F::epsilon()
+            {
+                break
+            }
             v1 = v1 - delta;
+            if !(v1 >= bracket[0] && v1 <= bracket[1])
+            {
+                v1 = (bracket[0] + bracket[1])/(one + one)
+            }
         }
 
         v1 = v1.max(zero).min(v1_max);
@@ -0,0 +1,379 @@
+use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, param::FilterFloat};
+
+use crate::{f, tubes::Tube12AX7, PowerSupplyLoad};
+
+use super::{TriodeCalc, TriodeClassA, TriodeFilter, TriodeModelParam};
+
+/// How much of the cathode resistor is bypassed by a capacitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum CathodeBypass<F>
+{
+    /// The full signal current flows through the cathode resistor, degenerating the gain at all frequencies.
+    #[default]
+    None,
+    /// Only the part of the cathode resistor in excess of `r_u` is bypassed, so some degeneration remains at high frequencies.
+    Partial {
+        /// Unbypassed part of the cathode resistor
+        r_u: F,
+        /// Bypass capacitor
+        c_k: F
+    },
+    /// The whole cathode resistor is bypassed, giving full gain above the corner frequency.
+    Full {
+        /// Bypass capacitor
+        c_k: F
+    }
+}
+
+/// The cathode network of a self-biased triode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CathodeBiasParam<F>
+where
+    F: FilterFloat
+{
+    /// Cathode resistor
+    pub r_k: F,
+    pub bypass: CathodeBypass<F>
+}
+
+/// A common-cathode triode biased by its own cathode current.
+///
+/// The cathode voltage is solved together with the plate current every sample instead of being fixed. When the stage clips
+/// asymmetrically, the average cathode current changes and the bypass capacitor slowly drifts to a new bias, making the gain and
+/// headroom sag and recover with the signal envelope.
+///
+/// The plate is solved by `C` at the grid-cathode voltage of each guess of the cathode voltage, a few times per sample. As with `Triode`,
+/// `v_pp` of the calc is the plate-cathode supply, and `v_c` an additional fixed grid bias, normally zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CathodeBiasedTriode<F, M = Tube12AX7, FI = FirstOrderRCFilter<LowPass, F>, FO = FirstOrderRCFilter<LowPass, F>, C = TriodeClassA<F>>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    C: TriodeCalc<F, M>,
+    FI: TriodeFilter<F, M>,
+    FO: TriodeFilter<F, M>
+{
+    calc: C,
+    input_filter: FI,
+    output_filter: FO,
+    cathode: CathodeBiasParam<F>,
+    miller_effect: F,
+    v_k: F,
+    v_c_k: F,
+    offset: F,
+    i_p: F,
+    model: M
+}
+
+impl<F, M, FI, FO, C> CathodeBiasedTriode<F, M, FI, FO, C>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    C: TriodeCalc<F, M>,
+    FI: TriodeFilter<F, M>,
+    FO: TriodeFilter<F, M>
+{
+    pub fn new(calc: C, model: M, cathode: CathodeBiasParam<F>) -> Self
+    {
+        let param = calc.param();
+        let input_filter = FI::new_input_filter(&model, param.r_i);
+        let output_filter = FO::new_output_filter(&model, param.r_p);
+        let mut triode = Self {
+            calc,
+            input_filter,
+            output_filter,
+            cathode,
+            miller_effect: F::one(),
+            v_k: F::zero(),
+            v_c_k: F::zero(),
+            offset: F::zero(),
+            i_p: F::zero(),
+            model
+        };
+        triode.calibrate();
+        triode
+    }
+
+    pub fn param(&self) -> &TriodeClassA<F>
+    {
+        self.calc.param()
+    }
+    pub fn param_mut(&mut self) -> &mut TriodeClassA<F>
+    {
+        self.calc.param_mut()
+    }
+    pub fn param_cathode(&self) -> &CathodeBiasParam<F>
+    {
+        &self.cathode
+    }
+    pub fn param_cathode_mut(&mut self) -> &mut CathodeBiasParam<F>
+    {
+        &mut self.cathode
+    }
+    pub fn model(&self) -> &M
+    {
+        &self.model
+    }
+    pub fn model_mut(&mut self) -> &mut M
+    {
+        &mut self.model
+    }
+    pub fn input_filter(&self) -> &FI
+    {
+        &self.input_filter
+    }
+    pub fn input_filter_mut(&mut self) -> &mut FI
+    {
+        &mut self.input_filter
+    }
+    pub fn output_filter(&self) -> &FO
+    {
+        &self.output_filter
+    }
+    pub fn output_filter_mut(&mut self) -> &mut FO
+    {
+        &mut self.output_filter
+    }
+
+    /// Cathode voltage during the last sample, which is the current self-bias of the stage.
+    pub fn v_k(&self) -> F
+    {
+        self.v_k
+    }
+    pub fn miller_effect(&self) -> F
+    {
+        self.miller_effect
+    }
+    /// Plate current drawn from the supply during the last sample.
+    pub fn i_p(&self) -> F
+    {
+        self.i_p
+    }
+
+    /// Unbypassed and bypassed part of the cathode resistor, with the bypass capacitor.
+    fn network(&self) -> (F, Option<(F, F)>)
+    {
+        let CathodeBiasParam {r_k, bypass} = self.cathode;
+        match bypass
+        {
+            CathodeBypass::None => (r_k, None),
+            CathodeBypass::Partial {r_u, c_k} => (r_u, Some((r_k - r_u, c_k))),
+            CathodeBypass::Full {c_k} => (F::zero(), Some((r_k, c_k)))
+        }
+    }
+
+    /// Voltage across the bypassed part `bypassed` of the cathode resistor at the end of the sample, given the plate current and the
+    /// voltage `v_c_k` at the start of the sample.
+    fn charge(bypassed: Option<(F, F)>, v_c_k: F, dt: Option<F>, i_p: F) -> F
+    {
+        match (bypassed, dt)
+        {
+            // Backward Euler
+            (Some((r_b, c_k)), Some(dt)) => (v_c_k + dt/c_k*i_p)/(F::one() + dt/(r_b*c_k)),
+            // DC, where the capacitor is open
+            (Some((r_b, _)), None) => r_b*i_p,
+            (None, _) => F::zero()
+        }
+    }
+
+    /// Solves the cathode voltage for the grid voltage `vg` relative to ground, with the bypass capacitor charged to `v_c_k` at the start
+    /// of the sample. Returns the cathode voltage with the plate voltage relative to the cathode and the gain.
+    fn solve(&mut self, dt: Option<F>, vg: F) -> (F, [F; 2])
+    {
+        let (r_u, bypassed) = self.network();
+        let TriodeClassA {r_p, v_pp, ..} = *self.param();
+        let Self {calc, model, v_c_k, ..} = self;
+
+        // Raising the cathode starves the triode, so the current falls and f is increasing
+        let f = |v_k: F| {
+            let [v_p, _] = calc.vp_a(model, vg - v_k);
+            let i_p = (v_pp - v_p)/r_p;
+            v_k - r_u*i_p - Self::charge(bypassed, *v_c_k, dt, i_p)
+        };
+
+        const NEWTON: usize = 16;
+        const DELTA: f64 = 1e-4;
+
+        let v_k = crate::newton_bracketed(f, self.v_k, [F::zero(), v_pp], v_pp*f!(DELTA), NEWTON);
+
+        (v_k, self.calc.vp_a(&self.model, vg - v_k))
+    }
+
+    fn update(&mut self, dt: Option<F>, vg: F) -> [F; 2]
+    {
+        let (v_k, [v_p, a]) = self.solve(dt, vg);
+        let TriodeClassA {r_p, v_pp, ..} = *self.param();
+
+        self.v_k = v_k;
+        self.i_p = (v_pp - v_p)/r_p;
+        let (_, bypassed) = self.network();
+        self.v_c_k = Self::charge(bypassed, self.v_c_k, dt, self.i_p);
+
+        [v_k + v_p, a]
+    }
+
+    /// Recomputes the quiescent point and charges the bypass capacitor to it. Must be called after changing the parameters or model.
+    pub fn calibrate(&mut self)
+    {
+        if let CathodeBypass::Partial {r_u, c_k: _} = self.cathode.bypass
+        {
+            assert!(r_u <= self.cathode.r_k, "Unbypassed part must not exceed the cathode resistor");
+        }
+
+        self.calc.reset();
+        self.v_k = F::zero();
+        [self.offset, _] = self.update(None, -self.param().v_c);
+    }
+
+    /// The plate output, relative to its quiescent voltage, with the grid driven by `x` relative to ground.
+    pub fn saturate(&mut self, rate: F, x: F) -> F
+    {
+        let param = *self.param();
+        let vg = self.input_filter.vg(&self.model, param, rate, x);
+
+        let [v_p, a] = self.update(Some(rate.recip()), vg);
+
+        self.miller_effect = F::one() + a.max(F::zero());
+        self.input_filter.update_miller_effect_input(&self.model, self.miller_effect);
+        self.output_filter.update_miller_effect_output(&self.model, self.miller_effect);
+
+        self.output_filter.y(rate, v_p - self.offset)
+    }
+}
+
+impl<F, M, FI, FO, C> PowerSupplyLoad<F> for CathodeBiasedTriode<F, M, FI, FO, C>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    FI: TriodeFilter<F, M>,
+    FO: TriodeFilter<F, M>,
+    C: TriodeCalc<F, M>
+{
+    fn i_supply(&self) -> F
+    {
+        self.i_p()
+    }
+    fn set_v_pp(&mut self, v_pp: F)
+    {
+        // The quiescent plate voltage follows the supply, so that sag does not step the output
+        if self.calc.track_v_pp(v_pp)
+        {
+            let (v_k, [v_p, _]) = self.solve(None, -self.param().v_c);
+            self.offset = v_k + v_p;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::{f32::consts::TAU, ops::Range};
+
+    use super::*;
+
+    const RATE: f32 = 48000.0;
+
+    const CALC: TriodeClassA<f32> = TriodeClassA {
+        r_i: 1e3,
+        r_p: 100e3,
+        v_pp: 300.0,
+        v_c: 0.0
+    };
+
+    fn cathode(bypass: CathodeBypass<f32>) -> CathodeBiasParam<f32>
+    {
+        CathodeBiasParam {
+            r_k: 1.5e3,
+            bypass
+        }
+    }
+
+    const PARTIAL: CathodeBypass<f32> = CathodeBypass::Partial {
+        r_u: 500.0,
+        c_k: 22e-6
+    };
+    const FULL: CathodeBypass<f32> = CathodeBypass::Full {
+        c_k: 22e-6
+    };
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = 0.0..0.4;
+        // One sample per plotted point
+        const RATE: f32 = 512.0/(RANGE.end - RANGE.start);
+        const FREQ: f32 = 80.0;
+
+        let mut t0 = CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(CathodeBypass::None));
+        let mut t1 = CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(PARTIAL));
+        let mut t2 = CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(FULL));
+
+        // A loud burst, followed by a quiet tail, with the cathode voltage of the fully bypassed stage
+        crate::tests::plot(
+            "CathodeBiasedTriode",
+            RANGE,
+            |t| {
+                let a = if t < RANGE.end/2.0 {20.0} else {0.5};
+                let x = a*(TAU*FREQ*t).sin();
+                [
+                    t0.saturate(RATE, x),
+                    t1.saturate(RATE, x),
+                    t2.saturate(RATE, x),
+                    10.0*t2.v_k()
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn bypass()
+    {
+        const FREQ: f32 = 1e3;
+        const N: usize = 4800;
+
+        let peak = |bypass, a: f32| {
+            let mut triode = CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(bypass));
+            let v_k = triode.v_k();
+            let y = (0..N).map(|n| triode.saturate(RATE, a*(TAU*FREQ*n as f32/RATE).sin()).abs())
+                .skip(N/2)
+                .fold(0.0, f32::max);
+            (y, triode.v_k() - v_k)
+        };
+
+        // More bypass, more gain
+        let [none, partial, full] = [CathodeBypass::None, PARTIAL, FULL].map(|bypass| peak(bypass, 0.05).0);
+        assert!(none < partial && partial < full);
+
+        // Asymmetric clipping changes the average cathode current, so the bypass capacitor drifts to a new bias
+        let (_, shift) = peak(FULL, 40.0);
+        assert!(shift.abs() > 0.1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn cache()
+    {
+        const FREQ: f32 = 440.0;
+        const N: usize = 2400;
+
+        // A cached calc settles at the same bias and follows the same waveform
+        let mut direct = CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(FULL));
+        let mut cached = CathodeBiasedTriode::<_, _, FirstOrderRCFilter<LowPass, _>, FirstOrderRCFilter<LowPass, _>, _>::new(CALC.cache(-40.0..40.0, 2048), Tube12AX7, cathode(FULL));
+        assert!((direct.v_k() - cached.v_k()).abs() < 1e-2);
+        for n in 0..N
+        {
+            let x = 4.0*(TAU*FREQ*n as f32/RATE).sin();
+            assert!((direct.saturate(RATE, x) - cached.saturate(RATE, x)).abs() < 0.5);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn unbypassed_exceeds_cathode_resistor()
+    {
+        CathodeBiasedTriode::<_, _>::new(CALC, Tube12AX7, cathode(CathodeBypass::Partial {
+            r_u: 2e3,
+            c_k: 22e-6
+        }));
+    }
+}
\ No newline at end of file
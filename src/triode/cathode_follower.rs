@@ -0,0 +1,171 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, tubes::Tube12AX7};
+
+use super::TriodeModelParam;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CathodeFollowerParam<F>
+where
+    F: FilterFloat
+{
+    /// Cathode resistor
+    pub r_k: F,
+    /// Grid stopper resistor
+    pub r_g: F,
+    /// Supply voltage, directly at the plate
+    pub v_pp: F,
+    /// Grid bias voltage, relative to ground
+    pub v_g: F
+}
+
+/// A common-plate buffer, where the cathode follows the grid with slightly less than unity gain.
+///
+/// When the grid is driven above the cathode, grid current flows through the grid stopper and rounds off the positive peaks, while negative
+/// peaks clip hard once the triode cuts off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CathodeFollower<F, M = Tube12AX7>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    param: CathodeFollowerParam<F>,
+    v_k: F,
+    model: M
+}
+
+impl<F, M> CathodeFollower<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    pub fn new(param: CathodeFollowerParam<F>) -> Self
+    where
+        M: Default
+    {
+        Self::with_model(param, M::default())
+    }
+
+    pub fn with_model(param: CathodeFollowerParam<F>, model: M) -> Self
+    {
+        let mut follower = Self {
+            param,
+            v_k: F::zero(),
+            model
+        };
+        follower.calibrate();
+        follower
+    }
+
+    pub fn param(&self) -> &CathodeFollowerParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut CathodeFollowerParam<F>
+    {
+        &mut self.param
+    }
+    pub fn model(&self) -> &M
+    {
+        &self.model
+    }
+    pub fn model_mut(&mut self) -> &mut M
+    {
+        &mut self.model
+    }
+
+    /// Quiescent cathode voltage.
+    pub fn v_k(&self) -> F
+    {
+        self.v_k
+    }
+
+    /// Recomputes the quiescent cathode voltage. Must be called after changing the parameters or model.
+    pub fn calibrate(&mut self)
+    {
+        self.v_k = self.param.v_g.max(F::zero()).min(self.param.v_pp);
+        self.v_k = self.v_cathode(F::zero());
+    }
+
+    fn v_cathode(&self, x: F) -> F
+    {
+        /// Softness of the onset of grid conduction (Volt)
+        const V_KNEE: f64 = 0.1;
+
+        let CathodeFollowerParam {r_k, r_g, v_pp, v_g} = self.param;
+        let model = self.model.triode();
+
+        let v_knee = f!(V_KNEE);
+        let r_gi = f!(model.r_gi) + r_g;
+        let v_s = v_g + x;
+
+        // Raising the cathode starves the triode and shuts off the grid current, so f is increasing
+        let f = |v_k: F| {
+            let i_g = v_knee*crate::exp_ln_1p((v_s - v_k)/v_knee)/r_gi;
+            let v_gk = v_s - i_g*r_g - v_k;
+            let i_p = model.i_p(v_gk, v_pp - v_k);
+            v_k - r_k*(i_p + i_g)
+        };
+
+        const NEWTON: usize = 16;
+        const DELTA: f64 = 1e-4;
+
+        crate::newton_bracketed(f, self.v_k, [F::zero(), v_pp.max(v_s)], v_pp*f!(DELTA), NEWTON)
+    }
+
+    /// The cathode output, relative to its quiescent voltage.
+    pub fn saturate(&self, x: F) -> F
+    {
+        self.v_cathode(x) - self.v_k
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::tubes::{Tube12AU7, Tube6DJ8};
+
+    use super::*;
+
+    fn param() -> CathodeFollowerParam<f32>
+    {
+        CathodeFollowerParam {
+            r_k: 100e3,
+            r_g: 470e3,
+            v_pp: 350.0,
+            v_g: 180.0
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -250.0..250.0;
+
+        let t0 = CathodeFollower::<_, Tube12AX7>::new(param());
+        let t1 = CathodeFollower::<_, Tube12AU7>::new(param());
+        let t2 = CathodeFollower::<_, Tube6DJ8>::new(param());
+
+        crate::tests::plot(
+            "CathodeFollower",
+            RANGE,
+            |x| [t0.saturate(x), t1.saturate(x), t2.saturate(x)]
+        )
+    }
+
+    #[test]
+    fn asymmetric()
+    {
+        let follower = CathodeFollower::<_, Tube12AU7>::new(param());
+
+        // Close to unity gain for small signals
+        let y = follower.saturate(1.0);
+        assert!(y > 0.95 && y < 1.0);
+
+        // Positive peaks are held back by grid current, negative peaks only by cutoff
+        assert!(follower.saturate(100.0) < -follower.saturate(-100.0));
+        assert!(follower.saturate(-300.0) >= -follower.v_k());
+    }
+}
\ No newline at end of file
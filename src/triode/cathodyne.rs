@@ -0,0 +1,165 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, tubes::Tube12AX7};
+
+use super::{TriodeCalc, TriodeClassA, TriodeModelParam};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CathodyneParam<F>
+where
+    F: FilterFloat
+{
+    /// Plate resistor
+    pub r_p: F,
+    /// Cathode resistor
+    pub r_k: F,
+    /// Supply voltage
+    pub v_pp: F,
+    /// Grid bias voltage, relative to ground
+    pub v_g: F
+}
+
+/// A split-load phase inverter, with equal loads at the plate and cathode of a single triode.
+///
+/// The outputs are close to balanced until the triode saturates or cuts off, where the cathode output, with its low impedance, clips differently
+/// from the plate output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Cathodyne<F, M = Tube12AX7>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    param: CathodyneParam<F>,
+    i_q: F,
+    model: M
+}
+
+impl<F, M> Cathodyne<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    pub fn new(param: CathodyneParam<F>) -> Self
+    where
+        M: Default
+    {
+        Self::with_model(param, M::default())
+    }
+
+    pub fn with_model(param: CathodyneParam<F>, model: M) -> Self
+    {
+        let mut cathodyne = Self {
+            param,
+            i_q: F::zero(),
+            model
+        };
+        cathodyne.calibrate();
+        cathodyne
+    }
+
+    pub fn param(&self) -> &CathodyneParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut CathodyneParam<F>
+    {
+        &mut self.param
+    }
+    pub fn model(&self) -> &M
+    {
+        &self.model
+    }
+    pub fn model_mut(&mut self) -> &mut M
+    {
+        &mut self.model
+    }
+
+    /// Recomputes the quiescent current. Must be called after changing the parameters or model.
+    pub fn calibrate(&mut self)
+    {
+        self.i_q = F::zero();
+        self.i_q = self.i_p(F::zero());
+    }
+
+    fn i_p(&self, x: F) -> F
+    {
+        let CathodyneParam {r_p, r_k, v_pp, v_g} = self.param;
+
+        // Both loads are in series with the tube, and the cathode load degenerates the grid voltage
+        let r = r_p + r_k;
+        let mut calc = TriodeClassA {
+            r_i: F::zero(),
+            r_p: r,
+            v_pp,
+            v_c: F::zero()
+        };
+
+        let f = |i: F| {
+            let [v_p, _] = calc.vp_a(&self.model, v_g + x - i*r_k);
+            i - (v_pp - v_p)/r
+        };
+
+        const NEWTON: usize = 16;
+        const DELTA: f64 = 1e-4;
+
+        let i_max = v_pp/r;
+        crate::newton_bracketed(f, self.i_q, [F::zero(), i_max], i_max*f!(DELTA), NEWTON)
+    }
+
+    /// The cathode and plate outputs, relative to their quiescent voltages, in phase and in antiphase with the input respectively.
+    pub fn saturate(&self, x: F) -> [F; 2]
+    {
+        let i = self.i_p(x) - self.i_q;
+        [i*self.param.r_k, -i*self.param.r_p]
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::tubes::{Tube12AU7, Tube6DJ8};
+
+    use super::*;
+
+    fn param() -> CathodyneParam<f32>
+    {
+        CathodyneParam {
+            r_p: 56e3,
+            r_k: 56e3,
+            v_pp: 350.0,
+            v_g: 90.0
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -120.0..120.0;
+
+        let t0 = Cathodyne::<_, Tube12AX7>::new(param());
+        let t1 = Cathodyne::<_, Tube12AU7>::new(param());
+        let t2 = Cathodyne::<_, Tube6DJ8>::new(param());
+
+        crate::tests::plot(
+            "Cathodyne",
+            RANGE,
+            |x| {
+                let [y0, z0] = t0.saturate(x);
+                let [y1, z1] = t1.saturate(x);
+                let [y2, z2] = t2.saturate(x);
+                [y0, z0, y1, z1, y2, z2]
+            }
+        )
+    }
+
+    #[test]
+    fn balanced()
+    {
+        let cathodyne = Cathodyne::<_, Tube12AU7>::new(param());
+        let [y, z] = cathodyne.saturate(1.0);
+        assert!(y > 0.0);
+        assert!((y + z).abs() < 1e-3*y);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,93 @@
+use core::ops::Range;
+
+use alloc::vec::Vec;
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::{f, LoadLine, PlateCurve};
+
+use super::{TriodeClassA, TriodeModelParam, TriodeParam};
+
+impl TriodeParam
+{
+    /// Plate curves for each grid-cathode voltage in `v_g`, each sampled at `n` evenly spaced plate voltages in `v_p`.
+    pub fn plate_curves(&self, v_g: &[f64], v_p: Range<f64>, n: usize) -> Vec<PlateCurve>
+    {
+        v_g.iter()
+            .map(|&v_g| PlateCurve::new(v_g, v_p.clone(), n, |v_p| self.i_p(v_g, v_p)))
+            .collect()
+    }
+}
+
+impl<F> TriodeClassA<F>
+where
+    F: FilterFloat
+{
+    /// The plate resistor's load line, intersecting the plate curve at grid-cathode voltage `v_g`.
+    ///
+    /// With `v_g = -v_c`, this is the quiescent operating point.
+    ///
+    /// The intersection is found by bisection on `TriodeParam::i_p`, independently of the solver `Triode` runs each sample, and is
+    /// thus a reference for it.
+    pub fn load_line<M>(&self, model: &M, v_g: f64) -> LoadLine
+    where
+        M: TriodeModelParam
+    {
+        let model = model.triode();
+        LoadLine::new(f!(self.v_pp; f64), f!(self.r_p; f64), v_g, |v_p| model.i_p(v_g, v_p))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{tubes::{Tube12AU7, Tube12AX7, Tube6DJ8}, write_plate_curves_csv};
+
+    use super::{super::TriodeCalc, *};
+
+    #[test]
+    fn plate_curves()
+    {
+        const N: usize = 256;
+        const V_G: [f64; 6] = [0.0, -0.5, -1.0, -1.5, -2.0, -2.5];
+
+        let curves = Tube12AU7.triode().plate_curves(&V_G, 0.0..400.0, N);
+
+        let mut csv = String::new();
+        write_plate_curves_csv(&curves, &mut csv).unwrap();
+        assert_eq!(csv.lines().count(), 1 + V_G.len()*N);
+
+        crate::plot::plot_curves(
+            "Plate curves of Tube12AU7",
+            "plots/triode_plate_curves.png",
+            [core::array::from_fn(|i| curves[0].v_p[i] as f32); V_G.len()],
+            core::array::from_fn(|j| core::array::from_fn::<_, N, _>(|i| curves[j].i_p[i] as f32*1e3))
+        )
+        .expect("Plot failed")
+    }
+
+    #[test]
+    fn load_line()
+    {
+        let mut param = TriodeClassA {
+            r_i: 1e3,
+            r_p: 220e3,
+            v_pp: 250.0,
+            v_c: 2.0
+        };
+
+        for model in [Tube6DJ8.triode(), Tube12AX7.triode(), Tube12AU7.triode()]
+        {
+            let load_line = param.load_line(&model, -param.v_c);
+            let [v_p, _] = param.vp_a(&model, -param.v_c);
+            assert!((load_line.v_p - v_p).abs() < 1e-3*param.v_pp);
+            assert!((load_line.i_p - model.i_p(load_line.v_g, load_line.v_p)).abs() < 1e-6*load_line.i_max);
+
+            // Agrees with the solver from cutoff into grid conduction
+            for v_g in [-20.0, -8.0, -4.0, -2.0, -1.0, 0.0, 1.0, 4.0]
+            {
+                let [v_p, _] = param.vp_a(&model, v_g);
+                assert!((param.load_line(&model, v_g).v_p - v_p).abs() < 1e-3*param.v_pp);
+            }
+        }
+    }
+}
\ No newline at end of file
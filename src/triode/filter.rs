@@ -1,13 +1,13 @@
-use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, param::{FilterFloat, RC}, rtf::{Rtf, StaticRtf}};
+use real_time_fir_iir_filters::{conf::LowPass, filters::iir::{first::FirstOrderRCFilter, second::SecondOrderRLCFilter}, param::{FilterFloat, RC, RLC}, rtf::{Rtf, StaticRtf}};
 
-use super::{TriodeClassA, TriodeModel};
+use super::{TriodeClassA, TriodeModelParam};
 
-use crate::f;
+use crate::{f, RtfFilter};
 
 pub trait TriodeCathodeFilter<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     type Param;
 
@@ -21,7 +21,7 @@ where
 impl<F, M> TriodeCathodeFilter<F, M> for ()
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
     type Param = ();
 
@@ -47,7 +47,7 @@ where
 impl<F, M> TriodeCathodeFilter<F, M> for FirstOrderRCFilter<LowPass, F, RC<F>>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     Self: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
 {
     type Param = RC<F>;
@@ -76,45 +76,45 @@ where
 pub trait TriodeFilter<F, M>
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
-    fn new_input_filter(r_i: F) -> Self;
-    fn new_output_filter(r_p: F) -> Self;
+    fn new_input_filter(model: &M, r_i: F) -> Self;
+    fn new_output_filter(model: &M, r_p: F) -> Self;
 
-    fn update_miller_effect_input(&mut self, miller_effect: F);
-    fn update_miller_effect_output(&mut self, miller_effect: F);
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F);
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F);
 
-    fn vg(&mut self, param: TriodeClassA<F>, rate: F, x: F) -> F;
+    fn vg(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F;
     fn y(&mut self, rate: F, y: F) -> F;
 }
 impl<F, M> TriodeFilter<F, M> for ()
 where
     F: FilterFloat,
-    M: TriodeModel
+    M: TriodeModelParam
 {
-    fn new_input_filter(_: F) -> Self
+    fn new_input_filter(_: &M, _: F) -> Self
     {
         
     }
 
-    fn new_output_filter(_: F) -> Self
+    fn new_output_filter(_: &M, _: F) -> Self
     {
         
     }
 
-    fn update_miller_effect_input(&mut self, _: F)
+    fn update_miller_effect_input(&mut self, _: &M, _: F)
     {
 
     }
-    fn update_miller_effect_output(&mut self, _: F)
+    fn update_miller_effect_output(&mut self, _: &M, _: F)
     {
 
     }
 
-    fn vg(&mut self, param: TriodeClassA<F>, _: F, x: F) -> F
+    fn vg(&mut self, model: &M, param: TriodeClassA<F>, _: F, x: F) -> F
     {
         let ri = param.r_i;
-        let rgi = f!(M::R_GI);
+        let rgi = f!(model.triode().r_gi);
 
         x*rgi/(rgi + ri) - param.v_c
     }
@@ -126,30 +126,34 @@ where
 impl<F, M> TriodeFilter<F, M> for FirstOrderRCFilter<LowPass, F, RC<F>>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     Self: Rtf<F = F, Outputs<F> = [F; 1]>
 {
-    fn new_input_filter(r_i: F) -> Self
+    fn new_input_filter(model: &M, r_i: F) -> Self
     {
-        FirstOrderRCFilter::new(RC {r: r_i, c: f!(M::C_CG + M::C_PG)})
+        let model = model.triode();
+        FirstOrderRCFilter::new(RC {r: r_i, c: f!(model.c_cg + model.c_pg)})
     }
-    fn new_output_filter(r_p: F) -> Self
+    fn new_output_filter(model: &M, r_p: F) -> Self
     {
-        FirstOrderRCFilter::new(RC {r: r_p, c: f!(M::C_CP + M::C_PG)})
+        let model = model.triode();
+        FirstOrderRCFilter::new(RC {r: r_p, c: f!(model.c_cp + model.c_pg)})
     }
 
-    fn update_miller_effect_input(&mut self, miller_effect: F)
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F)
     {
-        self.param.c = f!(M::C_CG) + f!(M::C_PG)*miller_effect;
+        let model = model.triode();
+        self.param.c = f!(model.c_cg) + f!(model.c_pg)*miller_effect;
     }
-    fn update_miller_effect_output(&mut self, miller_effect: F)
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F)
     {
-        self.param.c = f!(M::C_CP) + f!(M::C_PG)*miller_effect;
+        let model = model.triode();
+        self.param.c = f!(model.c_cp) + f!(model.c_pg)*miller_effect;
     }
 
-    fn vg(&mut self, param: TriodeClassA<F>, rate: F, x: F) -> F
+    fn vg(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F
     {
-        let [vg] = self.filter(rate, TriodeFilter::<F, M>::vg(&mut (), param, rate, x));
+        let [vg] = self.filter(rate, TriodeFilter::<F, M>::vg(&mut (), model, param, rate, x));
         vg
     }
     fn y(&mut self, rate: F, y: F) -> F
@@ -157,4 +161,96 @@ where
         let [y] = self.filter(rate, TriodeFilter::<F, M>::y(&mut (), rate, y));
         y
     }
+}
+/// The grid or plate network as an RLC low-pass, whose capacitance follows the Miller effect.
+///
+/// Starts without inductance, where it equals the RC low-pass. The inductance of the actual network is set through
+/// [`Triode::input_filter_mut`](crate::Triode::input_filter_mut) or [`Triode::output_filter_mut`](crate::Triode::output_filter_mut).
+impl<F, M> TriodeFilter<F, M> for SecondOrderRLCFilter<LowPass, F, RLC<F>>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    Self: Rtf<F = F, Outputs<F> = [F; 1]>
+{
+    fn new_input_filter(model: &M, r_i: F) -> Self
+    {
+        let model = model.triode();
+        Self::new(RLC {r: r_i, l: F::zero(), c: f!(model.c_cg + model.c_pg)})
+    }
+    fn new_output_filter(model: &M, r_p: F) -> Self
+    {
+        let model = model.triode();
+        Self::new(RLC {r: r_p, l: F::zero(), c: f!(model.c_cp + model.c_pg)})
+    }
+
+    fn update_miller_effect_input(&mut self, model: &M, miller_effect: F)
+    {
+        let model = model.triode();
+        self.param.c = f!(model.c_cg) + f!(model.c_pg)*miller_effect;
+    }
+    fn update_miller_effect_output(&mut self, model: &M, miller_effect: F)
+    {
+        let model = model.triode();
+        self.param.c = f!(model.c_cp) + f!(model.c_pg)*miller_effect;
+    }
+
+    fn vg(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F
+    {
+        let [vg] = self.filter(rate, TriodeFilter::<F, M>::vg(&mut (), model, param, rate, x));
+        vg
+    }
+    fn y(&mut self, rate: F, y: F) -> F
+    {
+        let [y] = self.filter(rate, TriodeFilter::<F, M>::y(&mut (), rate, y));
+        y
+    }
+}
+impl<F, M, T> TriodeFilter<F, M> for RtfFilter<T>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    T: Rtf<F = F, Outputs<F> = [F; 1]>
+{
+    fn new_input_filter(_: &M, _: F) -> Self
+    {
+        Self::default()
+    }
+    fn new_output_filter(_: &M, _: F) -> Self
+    {
+        Self::default()
+    }
+
+    fn update_miller_effect_input(&mut self, _: &M, _: F)
+    {
+
+    }
+    fn update_miller_effect_output(&mut self, _: &M, _: F)
+    {
+
+    }
+
+    fn vg(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F
+    {
+        let x = match self.filter_mut()
+        {
+            Some(filter) => {
+                let [x] = filter.filter(rate, x);
+                x
+            },
+            None => x
+        };
+        TriodeFilter::<F, M>::vg(&mut (), model, param, rate, x)
+    }
+    fn y(&mut self, rate: F, y: F) -> F
+    {
+        let y = TriodeFilter::<F, M>::y(&mut (), rate, y);
+        match self.filter_mut()
+        {
+            Some(filter) => {
+                let [y] = filter.filter(rate, y);
+                y
+            },
+            None => y
+        }
+    }
 }
\ No newline at end of file
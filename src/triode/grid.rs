@@ -0,0 +1,244 @@
+use real_time_fir_iir_filters::param::{FilterFloat, RC};
+
+use super::{TriodeClassA, TriodeModelParam};
+
+use crate::f;
+
+pub trait TriodeGridFilter<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    type Param;
+
+    fn new_grid_filter(param: Self::Param) -> Self;
+
+    fn param_grid(&self) -> &Self::Param;
+    fn param_grid_mut(&mut self) -> &mut Self::Param;
+
+    fn vg_grid(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F;
+}
+impl<F, M> TriodeGridFilter<F, M> for ()
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    type Param = ();
+
+    fn new_grid_filter(_: Self::Param) -> Self
+    {
+        
+    }
+
+    fn param_grid(&self) -> &Self::Param
+    {
+        self
+    }
+    fn param_grid_mut(&mut self) -> &mut Self::Param
+    {
+        self
+    }
+
+    fn vg_grid(&mut self, _: &M, _: TriodeClassA<F>, _: F, x: F) -> F
+    {
+        x
+    }
+}
+
+/// An input coupling capacitor and grid leak resistor, loaded by the grid current of the triode.
+///
+/// When the grid is driven above the cathode, the grid-cathode junction conducts like a diode and charges the coupling capacitor. The charge then
+/// biases the grid further negative until it leaks away through the grid leak resistor, which is the "blocking" distortion of overdriven tube stages.
+///
+/// The grid stopper `r_i` is taken to be in series with the internal grid resistance `R_GI` while conducting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GridConduction<F>
+where
+    F: FilterFloat
+{
+    /// Grid leak resistor and coupling capacitor
+    pub param: RC<F>,
+    /// Capacitor voltage
+    v: F,
+    /// Capacitor current
+    i: F
+}
+
+impl<F> GridConduction<F>
+where
+    F: FilterFloat
+{
+    pub fn new(param: RC<F>) -> Self
+    {
+        Self {
+            param,
+            v: F::zero(),
+            i: F::zero()
+        }
+    }
+
+    /// Voltage the coupling capacitor has been charged to.
+    pub fn bias_shift(&self) -> F
+    {
+        self.v
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.v = F::zero();
+        self.i = F::zero();
+    }
+}
+
+impl<F, M> TriodeGridFilter<F, M> for GridConduction<F>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    type Param = RC<F>;
+
+    fn new_grid_filter(param: Self::Param) -> Self
+    {
+        Self::new(param)
+    }
+
+    fn param_grid(&self) -> &Self::Param
+    {
+        &self.param
+    }
+    fn param_grid_mut(&mut self) -> &mut Self::Param
+    {
+        &mut self.param
+    }
+
+    fn vg_grid(&mut self, model: &M, param: TriodeClassA<F>, rate: F, x: F) -> F
+    {
+        /// Softness of the onset of grid conduction (Volt)
+        const V_KNEE: f64 = 0.1;
+
+        let one = F::one();
+        let two = one + one;
+
+        let v_knee = f!(V_KNEE);
+        let g_g = (f!(model.triode().r_gi) + param.r_i).recip();
+        let g_l = self.param.r.recip();
+        let g_c = two*self.param.c*rate;
+
+        // Trapezoidal companion model of the capacitor, seeded without grid current
+        let i_c = g_c*(x - self.v) - self.i;
+        let mut vg = i_c/(g_c + g_l);
+
+        const NEWTON: usize = 4;
+
+        for _ in 0..NEWTON
+        {
+            let u = (vg - param.v_c)/v_knee;
+            let i_g = v_knee*crate::exp_ln_1p(u)*g_g;
+            let di_g_dvg = g_g/(one + (-u).exp());
+
+            let f = i_c - vg*(g_c + g_l) - i_g;
+            let df_dvg = -(g_c + g_l) - di_g_dvg;
+
+            vg = vg - f/df_dvg;
+        }
+
+        let v = x - vg;
+        self.i = g_c*(v - self.v) - self.i;
+        self.v = v;
+
+        vg
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::tubes::{Tube12AU7, Tube12AX7};
+
+    use super::*;
+
+    #[test]
+    fn blocking()
+    {
+        const RATE: f64 = 48000.0;
+
+        let param = TriodeClassA {
+            r_i: 68e3,
+            r_p: 100e3,
+            v_pp: 250.0,
+            v_c: 1.5
+        };
+        let mut grid = GridConduction::new(RC {
+            r: 1e6,
+            c: 22e-9
+        });
+        let mut vg_grid = |x| TriodeGridFilter::<f64, Tube12AX7>::vg_grid(&mut grid, &Tube12AX7, param, RATE, x);
+        let mut burst = |amplitude| {
+            for n in 0..4800
+            {
+                vg_grid(amplitude*(TAU*440.0*n as f64/RATE).sin());
+            }
+            vg_grid(0.0)
+        };
+
+        // Small signals don't draw grid current, so they leave the bias alone
+        assert!(burst(0.5).abs() < 1e-2);
+
+        // A heavy burst charges the coupling capacitor and shifts the bias negative
+        let shift = burst(20.0);
+        assert!(shift < -1.0);
+
+        // The bias recovers through the grid leak after a few time constants
+        assert!(burst(0.0) > shift*0.05);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triode()
+    {
+        use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter};
+
+        use crate::Triode;
+
+        type Filter = FirstOrderRCFilter<LowPass, f64>;
+
+        const RATE: f64 = 48000.0;
+
+        let param = TriodeClassA {
+            r_i: 68e3,
+            r_p: 100e3,
+            v_pp: 250.0,
+            v_c: 1.5
+        };
+        let param_cathode = RC {
+            r: 1.5e3,
+            c: 22e-6
+        };
+        let param_grid = RC {
+            r: 1e6,
+            c: 22e-9
+        };
+
+        let mut t0 = Triode::<_, _>::new(param.cache(-50.0..50.0, 1024), Tube12AU7, param_cathode);
+        let mut t1 = Triode::<_, _, Filter, Filter, Filter, _, GridConduction<_>>::with_grid(
+            param.cache(-50.0..50.0, 1024),
+            Tube12AU7,
+            param_cathode,
+            param_grid
+        );
+        assert_eq!(*t1.param_grid(), param_grid);
+
+        // After a heavy burst, the blocked stage is biased colder and amplifies less
+        for n in 0..4800
+        {
+            let x = 20.0*(TAU*440.0*n as f64/RATE).sin();
+            t0.saturate(RATE, x);
+            t1.saturate(RATE, x);
+        }
+        let gain0 = (t0.saturate(RATE, 0.1) - t0.saturate(RATE, 0.0)).abs();
+        let gain1 = (t1.saturate(RATE, 0.1) - t1.saturate(RATE, 0.0)).abs();
+        assert!(gain1 < gain0);
+    }
+}
\ No newline at end of file
@@ -1,12 +1,21 @@
 use real_time_fir_iir_filters::{conf::LowPass, filters::iir::first::FirstOrderRCFilter, param::FilterFloat};
 
-use crate::tubes::Tube12AX7;
+use crate::{tubes::Tube12AX7, PowerSupplyLoad, TubeNoise};
 
 moddef::moddef!(
     flat(pub) mod {
+        bias,
         cache for cfg(feature = "alloc"),
+        cathode_bias,
+        cathode_follower,
+        cathodyne,
+        curves for cfg(feature = "alloc"),
+        grid,
+        long_tailed_pair,
         model,
-        param
+        paraphase,
+        param,
+        tube_chain
     },
     flat mod {
         calc,
@@ -17,21 +26,25 @@ moddef::moddef!(
 macro_rules! decl {
     ($calc:ty) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-        pub struct Triode<F, M = Tube12AX7, FI = FirstOrderRCFilter<LowPass, F>, FO = FirstOrderRCFilter<LowPass, F>, FC = FirstOrderRCFilter<LowPass, F>, C = $calc>
+        pub struct Triode<F, M = Tube12AX7, FI = FirstOrderRCFilter<LowPass, F>, FO = FirstOrderRCFilter<LowPass, F>, FC = FirstOrderRCFilter<LowPass, F>, C = $calc, FG = ()>
         where
             F: FilterFloat,
-            M: TriodeModel,
+            M: TriodeModelParam,
             C: TriodeCalc<F, M>,
             FI: TriodeFilter<F, M>,
             FO: TriodeFilter<F, M>,
-            FC: TriodeCathodeFilter<F, M>
+            FC: TriodeCathodeFilter<F, M>,
+            FG: TriodeGridFilter<F, M>
         {
             calc: C,
             input_filter: FI,
             output_filter: FO,
             cathode_filter: FC,
+            grid_filter: FG,
             miller_effect: F,
             offset: F,
+            i_p: F,
+            noise: Option<TubeNoise<F>>,
             model: M
         }
     };
@@ -42,28 +55,40 @@ decl!(TriodeCache<F, M>);
 #[cfg(not(feature = "alloc"))]
 decl!(TriodeClassA<F>);
 
-impl<F, M, C, FI, FO, FC> Triode<F, M, FI, FO, FC, C>
+impl<F, M, C, FI, FO, FC, FG> Triode<F, M, FI, FO, FC, C, FG>
 where
     F: FilterFloat,
-    M: TriodeModel,
+    M: TriodeModelParam,
     C: TriodeCalc<F, M>,
     FI: TriodeFilter<F, M>,
     FO: TriodeFilter<F, M>,
-    FC: TriodeCathodeFilter<F, M>
+    FC: TriodeCathodeFilter<F, M>,
+    FG: TriodeGridFilter<F, M>
 {
     pub fn new(calc: C, model: M, cathode: FC::Param) -> Self
+    where
+        FG::Param: Default
+    {
+        Self::with_grid(calc, model, cathode, Default::default())
+    }
+
+    pub fn with_grid(calc: C, model: M, cathode: FC::Param, grid: FG::Param) -> Self
     {
         let param = calc.param();
-        let input_filter = FI::new_input_filter(param.r_i);
-        let output_filter = FO::new_output_filter(param.r_p);
+        let input_filter = FI::new_input_filter(&model, param.r_i);
+        let output_filter = FO::new_output_filter(&model, param.r_p);
         let cathode_filter = FC::new_cathode_filter(cathode);
+        let grid_filter = FG::new_grid_filter(grid);
         let mut triode = Self {
             calc,
             input_filter,
             output_filter,
             cathode_filter,
+            grid_filter,
             miller_effect: F::one(),
             offset: F::zero(),
+            i_p: F::zero(),
+            noise: None,
             model,
         };
         triode.calibrate();
@@ -78,6 +103,43 @@ where
     {
         self.calc.param_mut()
     }
+    pub fn model(&self) -> &M
+    {
+        &self.model
+    }
+    pub fn model_mut(&mut self) -> &mut M
+    {
+        &mut self.model
+    }
+    pub fn input_filter(&self) -> &FI
+    {
+        &self.input_filter
+    }
+    pub fn input_filter_mut(&mut self) -> &mut FI
+    {
+        &mut self.input_filter
+    }
+    pub fn output_filter(&self) -> &FO
+    {
+        &self.output_filter
+    }
+    pub fn output_filter_mut(&mut self) -> &mut FO
+    {
+        &mut self.output_filter
+    }
+    pub fn noise(&self) -> Option<&TubeNoise<F>>
+    {
+        self.noise.as_ref()
+    }
+    pub fn noise_mut(&mut self) -> Option<&mut TubeNoise<F>>
+    {
+        self.noise.as_mut()
+    }
+    /// Adds noise sources at the grid, or removes them with `None`.
+    pub fn set_noise(&mut self, noise: Option<TubeNoise<F>>)
+    {
+        self.noise = noise
+    }
     pub fn param_cathode(&self) -> &FC::Param
     {
         self.cathode_filter.param_cathode()
@@ -86,10 +148,19 @@ where
     {
         self.cathode_filter.param_cathode_mut()
     }
+    pub fn param_grid(&self) -> &FG::Param
+    {
+        self.grid_filter.param_grid()
+    }
+    pub fn param_grid_mut(&mut self) -> &mut FG::Param
+    {
+        self.grid_filter.param_grid_mut()
+    }
 
     pub fn calibrate(&mut self)
     {
-        [self.offset, _] = self.calc.vp_a(-self.param().v_c);
+        [self.offset, _] = self.calc.vp_a(&self.model, -self.param().v_c);
+        self.i_p = (self.param().v_pp - self.offset)/self.param().r_p;
     }
 
     pub fn saturate(&mut self, rate: F, x: F) -> F
@@ -101,17 +172,23 @@ where
 
         let param = *self.param();
 
-        let mut vg = self.cathode_filter.vg_cathode(param, self.miller_effect, rate, x);
-        vg = self.input_filter.vg(param, rate, vg);
+        let mut vg = self.grid_filter.vg_grid(&self.model, param, rate, x);
+        vg = self.cathode_filter.vg_cathode(param, self.miller_effect, rate, vg);
+        vg = self.input_filter.vg(&self.model, param, rate, vg);
+        if let Some(noise) = &mut self.noise
+        {
+            vg = vg + noise.noise(rate, self.i_p, x);
+        }
 
-        let [vp, a] = self.calc.vp_a(vg);
+        let [vp, a] = self.calc.vp_a(&self.model, vg);
 
         let y = vp - self.offset;
+        self.i_p = (param.v_pp - vp)/param.r_p;
 
         self.miller_effect = one + a.max(zero);
 
-        self.input_filter.update_miller_effect_input(self.miller_effect);
-        self.output_filter.update_miller_effect_output(self.miller_effect);
+        self.input_filter.update_miller_effect_input(&self.model, self.miller_effect);
+        self.output_filter.update_miller_effect_output(&self.model, self.miller_effect);
 
         self.output_filter.y(rate, y)
     }
@@ -124,6 +201,35 @@ where
     {
         self.offset
     }
+    /// Plate current drawn from the supply during the last sample.
+    pub fn i_p(&self) -> F
+    {
+        self.i_p
+    }
+}
+
+impl<F, M, FI, FO, FC, C, FG> PowerSupplyLoad<F> for Triode<F, M, FI, FO, FC, C, FG>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    C: TriodeCalc<F, M>,
+    FI: TriodeFilter<F, M>,
+    FO: TriodeFilter<F, M>,
+    FC: TriodeCathodeFilter<F, M>,
+    FG: TriodeGridFilter<F, M>
+{
+    fn i_supply(&self) -> F
+    {
+        self.i_p()
+    }
+    fn set_v_pp(&mut self, v_pp: F)
+    {
+        // The quiescent plate voltage follows the supply, so that sag does not step the output
+        if self.calc.track_v_pp(v_pp)
+        {
+            [self.offset, _] = self.calc.vp_a(&self.model, -self.param().v_c);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -190,4 +296,51 @@ mod test
             ]
         )
     }
+
+    #[test]
+    fn vp_a()
+    {
+        // The plate voltage where the load line crosses the plate curve, by bisection
+        fn v_p(model: &TriodeParam, param: &TriodeClassA<f64>, v_g: f64) -> f64
+        {
+            let mut v_p = [0.0, param.v_pp];
+            for _ in 0..128
+            {
+                let v_mid = (v_p[0] + v_p[1])/2.0;
+                if param.v_pp - v_mid > param.r_p*model.i_p(v_g, v_mid)
+                {
+                    v_p[0] = v_mid
+                }
+                else
+                {
+                    v_p[1] = v_mid
+                }
+            }
+            (v_p[0] + v_p[1])/2.0
+        }
+
+        // Heavy and light loads, from hard conduction into deep cutoff
+        for (r_p, v_pp) in [(1e3, 450.0), (10e3, 300.0), (47e3, 24.0), (100e3, 300.0), (220e3, 250.0)]
+        {
+            let mut param = TriodeClassA {
+                r_i: 1e3,
+                r_p,
+                v_pp,
+                v_c: 0.0
+            };
+            for model in [Tube6DJ8.triode(), Tube12AX7.triode(), Tube12AU7.triode(), Tube6L6CG.triode(), Tube6550.triode(), TubeKT88.triode()]
+            {
+                for i in 0..=400
+                {
+                    let v_g = -40.0 + i as f64*0.2;
+                    let [vp, a] = param.vp_a(&model, v_g);
+                    assert!((vp - v_p(&model, &param, v_g)).abs() < 1e-9*v_pp);
+
+                    let dv_g = 1e-4;
+                    let a_diff = (v_p(&model, &param, v_g - dv_g) - v_p(&model, &param, v_g + dv_g))/(dv_g + dv_g);
+                    assert!((a - a_diff).abs() < 1e-3*(1.0 + a_diff.abs()));
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -1,3 +1,7 @@
+use num::Float;
+
+use crate::f;
+
 pub trait TriodeModel
 {
     const MU: f64;
@@ -9,4 +13,76 @@ pub trait TriodeModel
     const C_PG: f64;
     const C_CP: f64;
     const R_GI: f64;
+    /// Maximum plate dissipation (Watt)
+    const P_MAX: f64 = f64::INFINITY;
+}
+
+/// Parameters of a triode model only known at runtime, e.g. imported from a Koren SPICE subcircuit.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TriodeParam
+{
+    pub mu: f64,
+    pub ex: f64,
+    pub k_g1: f64,
+    pub k_p: f64,
+    pub k_vb: f64,
+    pub c_cg: f64,
+    pub c_pg: f64,
+    pub c_cp: f64,
+    pub r_gi: f64,
+    /// Maximum plate dissipation (Watt)
+    pub p_max: f64
+}
+
+impl TriodeParam
+{
+    /// Plate current by Koren's equation, given grid-cathode and plate-cathode voltages.
+    pub fn i_p<F>(&self, v_g: F, v_p: F) -> F
+    where
+        F: Float
+    {
+        let k_p = f!(self.k_p);
+        let e1 = v_p/k_p*crate::exp_ln_1p(k_p*(f!(self.mu.recip()) + v_g/(f!(self.k_vb) + v_p*v_p).sqrt()));
+        if e1 > F::zero()
+        {
+            f!(2.0/self.k_g1)*e1.powf(f!(self.ex))
+        }
+        else
+        {
+            F::zero()
+        }
+    }
+}
+
+/// A triode model, either a preset implementing `TriodeModel` or a `TriodeParam`.
+pub trait TriodeModelParam
+{
+    fn triode(&self) -> TriodeParam;
+}
+impl<M> TriodeModelParam for M
+where
+    M: TriodeModel
+{
+    fn triode(&self) -> TriodeParam
+    {
+        TriodeParam {
+            mu: M::MU,
+            ex: M::EX,
+            k_g1: M::K_G1,
+            k_p: M::K_P,
+            k_vb: M::K_VB,
+            c_cg: M::C_CG,
+            c_pg: M::C_PG,
+            c_cp: M::C_CP,
+            r_gi: M::R_GI,
+            p_max: M::P_MAX
+        }
+    }
+}
+impl TriodeModelParam for TriodeParam
+{
+    fn triode(&self) -> TriodeParam
+    {
+        *self
+    }
 }
\ No newline at end of file
@@ -9,7 +9,7 @@ use num::Float;
 use real_time_fir_iir_filters::param::FilterFloat;
 
 #[cfg(feature = "alloc")]
-use super::{TriodeModel, TriodeCache};
+use super::{TriodeModelParam, TriodeCache, TriodeHermiteCache};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct TriodeClassA<F>
@@ -32,7 +32,7 @@ where
 {
     pub fn cache_in<M, A>(self, range: Range<F>, resolution: usize, alloc: A) -> TriodeCache<F, M, A>
     where
-        M: TriodeModel,
+        M: TriodeModelParam,
         A: Allocator + Clone
     {
         TriodeCache::new_in(self, range, resolution, alloc)
@@ -40,8 +40,23 @@ where
 
     pub fn cache<M>(self, range: Range<F>, resolution: usize) -> TriodeCache<F, M>
     where
-        M: TriodeModel
+        M: TriodeModelParam
     {
         TriodeCache::new(self, range, resolution)
     }
+
+    pub fn hermite_cache_in<M, A>(self, range: Range<F>, resolution: usize, alloc: A) -> TriodeHermiteCache<F, M, A>
+    where
+        M: TriodeModelParam,
+        A: Allocator + Clone
+    {
+        TriodeHermiteCache::new_in(self, range, resolution, alloc)
+    }
+
+    pub fn hermite_cache<M>(self, range: Range<F>, resolution: usize) -> TriodeHermiteCache<F, M>
+    where
+        M: TriodeModelParam
+    {
+        TriodeHermiteCache::new(self, range, resolution)
+    }
 }
\ No newline at end of file
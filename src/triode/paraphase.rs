@@ -0,0 +1,171 @@
+use real_time_fir_iir_filters::param::FilterFloat;
+
+use crate::tubes::Tube12AX7;
+
+use super::{TriodeCalc, TriodeClassA, TriodeModelParam};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ParaphaseParam<F>
+where
+    F: FilterFloat
+{
+    /// Plate resistor of each triode
+    pub r_p: F,
+    /// Supply voltage
+    pub v_pp: F,
+    /// Cathode voltage of each triode
+    pub v_c: F,
+    /// Divider ratio feeding the second triode, relative to the inverse of the first triode's quiescent gain
+    pub trim: F
+}
+
+/// A phase inverter where a second common-cathode triode inverts a divided-down copy of the first triode's output.
+///
+/// The divider is set from the small-signal gain, so the outputs only balance around the quiescent point. The second triode also amplifies
+/// the distortion of the first, making the in-phase output clip earlier and more asymmetrically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Paraphase<F, M = Tube12AX7>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    param: ParaphaseParam<F>,
+    divider: F,
+    offset: [F; 2],
+    model_a: M,
+    model_b: M
+}
+
+impl<F, M> Paraphase<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam
+{
+    pub fn new(param: ParaphaseParam<F>) -> Self
+    where
+        M: Default
+    {
+        Self::with_models(param, M::default(), M::default())
+    }
+
+    pub fn with_models(param: ParaphaseParam<F>, model_a: M, model_b: M) -> Self
+    {
+        let mut paraphase = Self {
+            param,
+            divider: F::zero(),
+            offset: [F::zero(); 2],
+            model_a,
+            model_b
+        };
+        paraphase.calibrate();
+        paraphase
+    }
+
+    pub fn param(&self) -> &ParaphaseParam<F>
+    {
+        &self.param
+    }
+    pub fn param_mut(&mut self) -> &mut ParaphaseParam<F>
+    {
+        &mut self.param
+    }
+    pub fn models(&self) -> [&M; 2]
+    {
+        [&self.model_a, &self.model_b]
+    }
+    pub fn models_mut(&mut self) -> [&mut M; 2]
+    {
+        [&mut self.model_a, &mut self.model_b]
+    }
+
+    fn calc(&self) -> TriodeClassA<F>
+    {
+        let ParaphaseParam {r_p, v_pp, v_c, trim: _} = self.param;
+        TriodeClassA {
+            r_i: F::zero(),
+            r_p,
+            v_pp,
+            v_c
+        }
+    }
+
+    /// Recomputes the quiescent plate voltages and the divider. Must be called after changing the parameters or models.
+    pub fn calibrate(&mut self)
+    {
+        let mut calc = self.calc();
+        let v_g = -self.param.v_c;
+        let [offset_a, a] = calc.vp_a(&self.model_a, v_g);
+        let [offset_b, _] = calc.vp_a(&self.model_b, v_g);
+        self.offset = [offset_a, offset_b];
+        self.divider = self.param.trim/a;
+    }
+
+    /// The plate outputs of the second and first triode, relative to the quiescent voltage, in phase and in antiphase with the input
+    /// respectively.
+    pub fn saturate(&self, x: F) -> [F; 2]
+    {
+        let mut calc = self.calc();
+        let v_g = -self.param.v_c;
+
+        let [v_p_a, _] = calc.vp_a(&self.model_a, v_g + x);
+        let y_a = v_p_a - self.offset[0];
+        let [v_p_b, _] = calc.vp_a(&self.model_b, v_g + y_a*self.divider);
+        let y_b = v_p_b - self.offset[1];
+
+        [y_b, y_a]
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::ops::Range;
+
+    use crate::tubes::{Tube12AU7, Tube6DJ8};
+
+    use super::*;
+
+    fn param() -> ParaphaseParam<f32>
+    {
+        ParaphaseParam {
+            r_p: 100e3,
+            v_pp: 300.0,
+            v_c: 2.0,
+            trim: 1.0
+        }
+    }
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = -20.0..20.0;
+
+        let t0 = Paraphase::<_, Tube12AX7>::new(param());
+        let t1 = Paraphase::<_, Tube12AU7>::new(param());
+        let t2 = Paraphase::<_, Tube6DJ8>::new(param());
+
+        crate::tests::plot(
+            "Paraphase",
+            RANGE,
+            |x| {
+                let [y0, z0] = t0.saturate(x);
+                let [y1, z1] = t1.saturate(x);
+                let [y2, z2] = t2.saturate(x);
+                [y0, z0, y1, z1, y2, z2]
+            }
+        )
+    }
+
+    #[test]
+    fn balanced()
+    {
+        let paraphase = Paraphase::<_, Tube12AU7>::new(param());
+        let [y, z] = paraphase.saturate(1e-2);
+        assert!(y > 0.0);
+        assert!((y + z).abs() < 1e-2*y);
+
+        // Each output is relative to the quiescent point of its own triode
+        let mismatched = Paraphase::with_models(param(), Tube12AX7.triode(), Tube12AU7.triode());
+        assert_eq!(mismatched.saturate(0.0), [0.0; 2]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,322 @@
+use real_time_fir_iir_filters::{conf::{HighPass, LowPass}, filters::iir::first::FirstOrderRCFilter, param::{FilterFloat, RC}, rtf::{Rtf, StaticRtf}};
+
+use crate::{f, tubes::Tube12AX7};
+
+use super::{GridConduction, Triode, TriodeCathodeFilter, TriodeClassA, TriodeFilter, TriodeModelParam};
+
+type StageTriode<F, M> = Triode<F, M, FirstOrderRCFilter<LowPass, F>, FirstOrderRCFilter<LowPass, F>, FirstOrderRCFilter<LowPass, F>, TriodeClassA<F>, GridConduction<F>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TubeStageParam<F>
+where
+    F: FilterFloat
+{
+    pub triode: TriodeClassA<F>,
+    /// Cathode resistor and bypass capacitor
+    pub cathode: RC<F>,
+    /// Grid leak resistor and coupling capacitor at the input of the stage
+    pub coupling: RC<F>,
+    /// Series and shunt resistor of the divider in front of the stage, e.g. a volume pot. A zero series resistor passes the signal through.
+    pub divider: [F; 2],
+    /// Bright capacitor across the series resistor of the divider
+    pub bright: Option<F>
+}
+
+/// A common-cathode triode gain stage, preceded by an interstage divider and a coupling capacitor.
+#[derive(Debug, Clone)]
+pub struct TubeStage<F, M = Tube12AX7>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, M> + TriodeCathodeFilter<F, M, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    triode: StageTriode<F, M>,
+    divider: [F; 2],
+    bright: Option<FirstOrderRCFilter<HighPass, F, RC<F>>>
+}
+
+impl<F, M> TubeStage<F, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, M> + TriodeCathodeFilter<F, M, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    pub fn new(param: TubeStageParam<F>, model: M) -> Self
+    {
+        let TubeStageParam {triode, cathode, coupling, divider, bright} = param;
+        let mut stage = Self {
+            triode: Triode::with_grid(triode, model, cathode, coupling),
+            divider,
+            bright: None
+        };
+        stage.set_divider(divider, bright);
+        stage
+    }
+
+    pub fn triode(&self) -> &StageTriode<F, M>
+    {
+        &self.triode
+    }
+    pub fn triode_mut(&mut self) -> &mut StageTriode<F, M>
+    {
+        &mut self.triode
+    }
+    pub fn divider(&self) -> [F; 2]
+    {
+        self.divider
+    }
+    pub fn bright(&self) -> Option<F>
+    {
+        self.bright.as_ref()
+            .map(|bright| bright.get_param().c)
+    }
+
+    /// Changes the interstage divider, e.g. when turning a volume pot.
+    pub fn set_divider(&mut self, divider: [F; 2], bright: Option<F>)
+    {
+        self.divider = divider;
+        let [r_1, r_2] = divider;
+        let r = if r_1 + r_2 > F::zero()
+        {
+            r_1*r_2/(r_1 + r_2)
+        }
+        else
+        {
+            F::zero()
+        };
+        match (&mut self.bright, bright)
+        {
+            (Some(filter), Some(c)) => *filter.get_param_mut() = RC {r, c},
+            (bright, c) => *bright = c.map(|c| FirstOrderRCFilter::new(RC {r, c}))
+        }
+    }
+
+    fn attenuation(&self) -> F
+    {
+        let [r_1, r_2] = self.divider;
+        if r_1 > F::zero()
+        {
+            r_2/(r_1 + r_2)
+        }
+        else
+        {
+            F::one()
+        }
+    }
+
+    pub fn saturate(&mut self, rate: F, x: F) -> F
+    {
+        let a = self.attenuation();
+
+        // The bright capacitor shelves the divider up to unity at high frequencies
+        let x = match &mut self.bright
+        {
+            Some(bright) => {
+                let [x_high] = bright.filter(rate, x);
+                a*x + (F::one() - a)*x_high
+            },
+            None => a*x
+        };
+
+        self.triode.saturate(rate, x)
+    }
+}
+
+/// A preamp of `N` cascaded triode gain stages. Each stage inverts the signal.
+#[derive(Debug, Clone)]
+pub struct TubeChain<F, const N: usize, M = Tube12AX7>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, M> + TriodeCathodeFilter<F, M, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    stages: [TubeStage<F, M>; N]
+}
+
+impl<F, const N: usize, M> TubeChain<F, N, M>
+where
+    F: FilterFloat,
+    M: TriodeModelParam,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, M> + TriodeCathodeFilter<F, M, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    pub fn new(param: [TubeStageParam<F>; N]) -> Self
+    where
+        M: Default
+    {
+        Self::with_models(param.map(|param| (param, M::default())))
+    }
+
+    pub fn with_models(stages: [(TubeStageParam<F>, M); N]) -> Self
+    {
+        Self {
+            stages: stages.map(|(param, model)| TubeStage::new(param, model))
+        }
+    }
+
+    pub fn stages(&self) -> &[TubeStage<F, M>; N]
+    {
+        &self.stages
+    }
+    pub fn stages_mut(&mut self) -> &mut [TubeStage<F, M>; N]
+    {
+        &mut self.stages
+    }
+
+    pub fn saturate(&mut self, rate: F, x: F) -> F
+    {
+        self.stages.iter_mut()
+            .fold(x, |x, stage| stage.saturate(rate, x))
+    }
+}
+
+impl<F> TubeChain<F, 2>
+where
+    F: FilterFloat,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, Tube12AX7> + TriodeCathodeFilter<F, Tube12AX7, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    /// Two cascaded 12AX7 stages with a bright volume pot in between, as in the normal channel of many classic amps.
+    pub fn cascade() -> Self
+    {
+        Self::new([
+            TubeStageParam {
+                triode: TriodeClassA {
+                    r_i: f!(1e3),
+                    r_p: f!(100e3),
+                    v_pp: f!(300.0),
+                    v_c: f!(1.5)
+                },
+                cathode: RC {
+                    r: f!(1.5e3),
+                    c: f!(22e-6)
+                },
+                coupling: RC {
+                    r: f!(1e6),
+                    c: f!(22e-9)
+                },
+                divider: [F::zero(), F::one()],
+                bright: None
+            },
+            TubeStageParam {
+                triode: TriodeClassA {
+                    r_i: f!(1e3),
+                    r_p: f!(100e3),
+                    v_pp: f!(300.0),
+                    v_c: f!(1.5)
+                },
+                cathode: RC {
+                    r: f!(1.5e3),
+                    c: f!(22e-6)
+                },
+                coupling: RC {
+                    r: f!(1e6),
+                    c: f!(22e-9)
+                },
+                divider: [f!(500e3), f!(500e3)],
+                bright: Some(f!(120e-12))
+            }
+        ])
+    }
+}
+
+impl<F> TubeChain<F, 4>
+where
+    F: FilterFloat,
+    FirstOrderRCFilter<LowPass, F>: TriodeFilter<F, Tube12AX7> + TriodeCathodeFilter<F, Tube12AX7, Param = RC<F>>,
+    FirstOrderRCFilter<HighPass, F, RC<F>>: Rtf<F = F, Outputs<F> = [F; 1], Param = RC<F>>
+{
+    /// Four cascaded 12AX7 stages, with a gain pot after the first and a cold biased clipper as the third, as in modern high gain amps.
+    pub fn high_gain() -> Self
+    {
+        let stage = |r_k: f64, c_k: f64, v_c: f64, c_coupling: f64, divider: [f64; 2], bright: Option<f64>| TubeStageParam {
+            triode: TriodeClassA {
+                r_i: f!(1e3),
+                r_p: f!(100e3),
+                v_pp: f!(350.0),
+                v_c: f!(v_c)
+            },
+            cathode: RC {
+                r: f!(r_k),
+                c: f!(c_k)
+            },
+            coupling: RC {
+                r: f!(1e6),
+                c: f!(c_coupling)
+            },
+            divider: divider.map(|r| f!(r)),
+            bright: bright.map(|c| f!(c))
+        };
+        Self::new([
+            stage(1.8e3, 1e-6, 1.5, 22e-9, [0.0, 1.0], None),
+            stage(1.5e3, 22e-6, 1.5, 22e-9, [500e3, 500e3], Some(470e-12)),
+            stage(10e3, 0.68e-6, 4.0, 2.2e-9, [470e3, 470e3], None),
+            stage(1.5e3, 22e-6, 1.5, 22e-9, [100e3, 470e3], None)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::{f32::consts::TAU, ops::Range};
+
+    use super::*;
+
+    #[test]
+    fn it_works()
+    {
+        const RANGE: Range<f32> = 0.0..0.02;
+        const OVERSAMPLING: usize = 4;
+        // Plots one in every few samples
+        const RATE: f32 = (512*OVERSAMPLING) as f32/(RANGE.end - RANGE.start);
+        const FREQUENCY: f32 = 100.0;
+        const AMPLITUDE: f32 = 0.5;
+
+        let mut t0 = TubeChain::cascade();
+        let mut t1 = TubeChain::high_gain();
+
+        let mut n = 0;
+        crate::tests::plot(
+            "TubeChain",
+            RANGE,
+            |_| {
+                let mut y = [0.0; 3];
+                for _ in 0..OVERSAMPLING
+                {
+                    let x = AMPLITUDE*(TAU*FREQUENCY*n as f32/RATE).sin();
+                    y = [x, t0.saturate(RATE, x), t1.saturate(RATE, x)];
+                    n += 1;
+                }
+                y
+            }
+        )
+    }
+
+    #[test]
+    fn high_gain()
+    {
+        const RATE: f32 = 48000.0;
+
+        let mut cascade = TubeChain::cascade();
+        let mut high_gain = TubeChain::high_gain();
+
+        let mut peak = [0.0f32; 2];
+        for n in 0..4800
+        {
+            let x = 0.01*(TAU*440.0*n as f32/RATE).sin();
+            let y = [cascade.saturate(RATE, x), high_gain.saturate(RATE, x)];
+            if n >= 2400
+            {
+                peak = [0, 1].map(|i| peak[i].max(y[i].abs()));
+            }
+        }
+
+        // More stages give more gain
+        assert!(peak[0] > 0.01);
+        assert!(peak[1] > peak[0]);
+    }
+}
\ No newline at end of file
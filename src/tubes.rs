@@ -15,6 +15,7 @@ impl TriodeModel for Tube6DJ8
     const C_PG: f64 = 2.1e-12;
     const C_CP: f64 = 0.7e-12;
     const R_GI: f64 = 2e3;
+    const P_MAX: f64 = 1.8;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
@@ -30,6 +31,7 @@ impl TriodeModel for Tube12AX7
     const C_PG: f64 = 850e-15;
     const C_CP: f64 = 12e-12;
     const R_GI: f64 = 1e3;
+    const P_MAX: f64 = 1.0;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
@@ -45,6 +47,7 @@ impl TriodeModel for Tube12AU7
     const C_PG: f64 = 2.4e-12;
     const C_CP: f64 = 900e-15;
     const R_GI: f64 = 2e3;
+    const P_MAX: f64 = 2.75;
 }
 
 // Pentodes:
@@ -62,6 +65,7 @@ impl TriodeModel for Tube6L6CG
     const C_PG: f64 = 2.2e-12;
     const C_CP: f64 = 1e-12;
     const R_GI: f64 = 2e3;
+    const P_MAX: f64 = 30.0;
 }
 impl PentodeModel for Tube6L6CG
 {
@@ -81,6 +85,7 @@ impl TriodeModel for Tube6550
     const C_PG: f64 = 850e-15;
     const C_CP: f64 = 12e-12;
     const R_GI: f64 = 1e3;
+    const P_MAX: f64 = 42.0;
 }
 impl PentodeModel for Tube6550
 {
@@ -100,6 +105,7 @@ impl TriodeModel for TubeKT88
     const C_PG: f64 = 850e-15;
     const C_CP: f64 = 12e-12;
     const R_GI: f64 = 1e3;
+    const P_MAX: f64 = 42.0;
 }
 impl PentodeModel for TubeKT88
 {